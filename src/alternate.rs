@@ -0,0 +1,135 @@
+use crate::NSUInteger;
+
+// ----------------------------------------------------------------------------
+
+/// Why a menu's alternate items would not be revealed by AppKit, see `Menu::check_alternates()`.
+///
+/// The index is the position of the offending alternate among the menu's realized items, where
+/// alternates follow their primary item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AlternateError {
+    /// The alternate has its own shortcut with a different key than its primary item.
+    KeyMismatch(usize),
+    /// The alternate uses the same modifiers as its primary item or an earlier alternate.
+    DuplicateModifiers(usize),
+}
+
+/// The key equivalent of a realized menu item.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) struct KeyEquivalent {
+    pub(crate) key: String,
+    pub(crate) mask: NSUInteger,
+    pub(crate) alternate: bool,
+}
+
+/// Checks that every alternate shares the key of the item it follows and has distinct
+/// modifiers, as AppKit requires.
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) fn check_alternates(items: &[KeyEquivalent]) -> Result<(), AlternateError> {
+    let mut group_start = 0;
+
+    for (index, item) in items.iter().enumerate() {
+        if !item.alternate {
+            group_start = index;
+            continue;
+        }
+
+        let group = &items[group_start..index];
+        if group[0].key != item.key {
+            return Err(AlternateError::KeyMismatch(index));
+        }
+        if group.iter().any(|other| other.mask == item.mask) {
+            return Err(AlternateError::DuplicateModifiers(index));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OPTION: NSUInteger = 1 << 19;
+    const COMMAND: NSUInteger = 1 << 20;
+
+    fn primary(key: &str, mask: NSUInteger) -> KeyEquivalent {
+        KeyEquivalent {
+            key: key.to_string(),
+            mask,
+            alternate: false,
+        }
+    }
+
+    fn alternate(key: &str, mask: NSUInteger) -> KeyEquivalent {
+        KeyEquivalent {
+            alternate: true,
+            ..primary(key, mask)
+        }
+    }
+
+    #[test]
+    fn accepts_alternates_with_distinct_modifiers() {
+        let items = [
+            primary("w", COMMAND),
+            alternate("w", COMMAND | OPTION),
+            primary("n", COMMAND),
+        ];
+
+        assert_eq!(check_alternates(&items), Ok(()));
+    }
+
+    #[test]
+    fn accepts_alternates_without_key() {
+        let items = [primary("", 0), alternate("", OPTION)];
+
+        assert_eq!(check_alternates(&items), Ok(()));
+    }
+
+    #[test]
+    fn rejects_different_key() {
+        let items = [primary("w", COMMAND), alternate("q", COMMAND | OPTION)];
+
+        assert_eq!(
+            check_alternates(&items),
+            Err(AlternateError::KeyMismatch(1))
+        );
+    }
+
+    #[test]
+    fn rejects_same_modifiers_as_primary() {
+        let items = [primary("w", COMMAND), alternate("w", COMMAND)];
+
+        assert_eq!(
+            check_alternates(&items),
+            Err(AlternateError::DuplicateModifiers(1))
+        );
+    }
+
+    #[test]
+    fn rejects_same_modifiers_as_earlier_alternate() {
+        let items = [
+            primary("w", COMMAND),
+            alternate("w", COMMAND | OPTION),
+            alternate("w", COMMAND | OPTION),
+        ];
+
+        assert_eq!(
+            check_alternates(&items),
+            Err(AlternateError::DuplicateModifiers(2))
+        );
+    }
+
+    #[test]
+    fn compares_alternates_with_their_own_primary() {
+        let items = [
+            primary("w", COMMAND),
+            alternate("w", COMMAND | OPTION),
+            primary("n", COMMAND),
+            alternate("n", COMMAND | OPTION),
+        ];
+
+        assert_eq!(check_alternates(&items), Ok(()));
+    }
+}
@@ -0,0 +1,71 @@
+use icrate::{
+    AppKit::NSMenuItem,
+    Foundation::{NSInteger, NSString},
+};
+use objc2::{class, msg_send};
+
+use crate::{id, nil};
+
+// ----------------------------------------------------------------------------
+
+/// A badge shown at the trailing edge of an item, see `MenuItem::badge(..)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Badge {
+    Count(usize),
+    Text(String),
+    /// A count of new items, e.g. "3 new".
+    NewItems(usize),
+    /// A count of alerts, e.g. "3 alerts".
+    Alerts(usize),
+    /// A count of updates, e.g. "3 updates".
+    Updates(usize),
+}
+
+impl Badge {
+    /// The text shown for this badge on systems without native badges.
+    pub fn text(&self) -> String {
+        match self {
+            Badge::Count(count) => count.to_string(),
+            Badge::Text(text) => text.clone(),
+            Badge::NewItems(count) => format!("{} new", count),
+            Badge::Alerts(1) => String::from("1 alert"),
+            Badge::Alerts(count) => format!("{} alerts", count),
+            Badge::Updates(1) => String::from("1 update"),
+            Badge::Updates(count) => format!("{} updates", count),
+        }
+    }
+
+    pub(crate) fn apply(&self, item: &NSMenuItem) {
+        unsafe {
+            let class = class!(NSMenuItemBadge);
+            let badge: id = match self {
+                Badge::Count(count) => {
+                    let alloc: id = msg_send![class, alloc];
+                    msg_send![alloc, initWithCount: *count as NSInteger]
+                }
+                Badge::Text(text) => {
+                    let text = NSString::from_str(text);
+                    let alloc: id = msg_send![class, alloc];
+                    msg_send![alloc, initWithString: &*text]
+                }
+                Badge::NewItems(count) => {
+                    let badge: id = msg_send![class, newItemsWithCount: *count as NSInteger];
+                    msg_send![badge, retain]
+                }
+                Badge::Alerts(count) => {
+                    let badge: id = msg_send![class, alertsWithCount: *count as NSInteger];
+                    msg_send![badge, retain]
+                }
+                Badge::Updates(count) => {
+                    let badge: id = msg_send![class, updatesWithCount: *count as NSInteger];
+                    msg_send![badge, retain]
+                }
+            };
+
+            if badge != nil {
+                let _: () = msg_send![item, setBadge: badge];
+                let _: () = msg_send![badge, release];
+            }
+        }
+    }
+}
@@ -0,0 +1,127 @@
+#[cfg(target_os = "macos")]
+use objc2::{class, msg_send, runtime::AnyClass, sel};
+#[cfg(target_os = "macos")]
+use once_cell::sync::Lazy;
+
+// ----------------------------------------------------------------------------
+
+/// Menu features that depend on the macOS version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Capabilities {
+    /// `NSMenuItem.sectionHeaderWithTitle:`, macOS 14.
+    pub(crate) section_headers: bool,
+    /// `NSMenu.presentationStyle` and `NSMenu.selectionMode`, macOS 14.
+    pub(crate) palettes: bool,
+    /// `NSMenuItemBadge`, macOS 14.
+    pub(crate) badges: bool,
+}
+
+#[cfg(target_os = "macos")]
+impl Capabilities {
+    pub(crate) fn current() -> Self {
+        static CURRENT: Lazy<Capabilities> = Lazy::new(Capabilities::detect);
+        *CURRENT
+    }
+
+    fn detect() -> Self {
+        unsafe {
+            let section_headers: bool = msg_send![
+                class!(NSMenuItem),
+                respondsToSelector: sel!(sectionHeaderWithTitle:)
+            ];
+            let palettes: bool = msg_send![
+                class!(NSMenu),
+                instancesRespondToSelector: sel!(setPresentationStyle:)
+            ];
+            let badges = AnyClass::get("NSMenuItemBadge").is_some();
+
+            Self {
+                section_headers,
+                palettes,
+                badges,
+            }
+        }
+    }
+}
+
+/// How a section header is realized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SectionHeaderStyle {
+    Native,
+    Fallback(FallbackHeader),
+}
+
+/// An ordinary item standing in for a section header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FallbackHeader {
+    pub(crate) title: String,
+    pub(crate) enabled: bool,
+}
+
+/// How a palette menu is realized.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PaletteStyle {
+    Native,
+    /// Ordinary items, the selection is shown with checkmarks.
+    Ordinary,
+}
+
+impl Capabilities {
+    /// How a section header with `title` is realized, a disabled item showing the title if
+    /// section headers aren't supported.
+    pub(crate) fn section_header_style(&self, title: &str) -> SectionHeaderStyle {
+        if self.section_headers {
+            SectionHeaderStyle::Native
+        } else {
+            SectionHeaderStyle::Fallback(FallbackHeader {
+                title: title.to_string(),
+                enabled: false,
+            })
+        }
+    }
+
+    pub(crate) fn palette_style(&self) -> PaletteStyle {
+        if self.palettes {
+            PaletteStyle::Native
+        } else {
+            PaletteStyle::Ordinary
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MACOS_13: Capabilities = Capabilities {
+        section_headers: false,
+        palettes: false,
+        badges: false,
+    };
+    const MACOS_14: Capabilities = Capabilities {
+        section_headers: true,
+        palettes: true,
+        badges: true,
+    };
+
+    #[test]
+    fn section_headers_fall_back_to_disabled_titles() {
+        assert_eq!(
+            MACOS_14.section_header_style("Recent"),
+            SectionHeaderStyle::Native
+        );
+        assert_eq!(
+            MACOS_13.section_header_style("Recent"),
+            SectionHeaderStyle::Fallback(FallbackHeader {
+                title: String::from("Recent"),
+                enabled: false,
+            })
+        );
+    }
+
+    #[test]
+    fn palettes_fall_back_to_ordinary_items() {
+        assert_eq!(MACOS_14.palette_style(), PaletteStyle::Native);
+        assert_eq!(MACOS_13.palette_style(), PaletteStyle::Ordinary);
+    }
+}
@@ -0,0 +1,180 @@
+use std::{ffi::c_void, ptr};
+
+use crate::hotkey::{hotkey_action, HotKeyBackend, HotKeyError};
+use crate::{KeyCode, NSUInteger};
+
+// ----------------------------------------------------------------------------
+
+type OSStatus = i32;
+type EventTargetRef = *mut c_void;
+type EventHandlerRef = *mut c_void;
+type EventHandlerCallRef = *mut c_void;
+type EventRef = *mut c_void;
+type EventHotKeyRef = *mut c_void;
+type EventHandlerProc = extern "C" fn(EventHandlerCallRef, EventRef, *mut c_void) -> OSStatus;
+
+#[repr(C)]
+struct EventTypeSpec {
+    event_class: u32,
+    event_kind: u32,
+}
+
+#[repr(C)]
+#[derive(Default)]
+struct EventHotKeyID {
+    signature: u32,
+    id: u32,
+}
+
+#[link(name = "Carbon", kind = "framework")]
+extern "C" {
+    fn GetApplicationEventTarget() -> EventTargetRef;
+    fn InstallEventHandler(
+        target: EventTargetRef,
+        handler: EventHandlerProc,
+        num_types: u32,
+        list: *const EventTypeSpec,
+        user_data: *mut c_void,
+        out_ref: *mut EventHandlerRef,
+    ) -> OSStatus;
+    fn RegisterEventHotKey(
+        key_code: u32,
+        modifiers: u32,
+        id: EventHotKeyID,
+        target: EventTargetRef,
+        options: u32,
+        out_ref: *mut EventHotKeyRef,
+    ) -> OSStatus;
+    fn UnregisterEventHotKey(hotkey: EventHotKeyRef) -> OSStatus;
+    fn GetEventParameter(
+        event: EventRef,
+        name: u32,
+        desired_type: u32,
+        actual_type: *mut u32,
+        buffer_size: usize,
+        actual_size: *mut usize,
+        data: *mut c_void,
+    ) -> OSStatus;
+}
+
+const SIGNATURE: u32 = u32::from_be_bytes(*b"ccmn");
+const K_EVENT_CLASS_KEYBOARD: u32 = u32::from_be_bytes(*b"keyb");
+const K_EVENT_HOT_KEY_PRESSED: u32 = 5;
+const K_EVENT_PARAM_DIRECT_OBJECT: u32 = u32::from_be_bytes(*b"----");
+const TYPE_EVENT_HOT_KEY_ID: u32 = u32::from_be_bytes(*b"hkid");
+const EVENT_HOT_KEY_EXISTS_ERR: OSStatus = -9878;
+const EVENT_NOT_HANDLED_ERR: OSStatus = -9874;
+
+/// Hotkeys registered with the Carbon Event Manager, the only API for global hotkeys that
+/// doesn't need accessibility permissions.
+#[derive(Default)]
+pub(crate) struct CarbonHotKeys {
+    handler_installed: bool,
+    refs: Vec<(u32, EventHotKeyRef)>,
+}
+
+impl HotKeyBackend for CarbonHotKeys {
+    fn register(
+        &mut self,
+        id: u32,
+        key_code: KeyCode,
+        mask: NSUInteger,
+    ) -> Result<(), HotKeyError> {
+        unsafe {
+            if !self.handler_installed {
+                let spec = EventTypeSpec {
+                    event_class: K_EVENT_CLASS_KEYBOARD,
+                    event_kind: K_EVENT_HOT_KEY_PRESSED,
+                };
+                let status = InstallEventHandler(
+                    GetApplicationEventTarget(),
+                    hotkey_pressed,
+                    1,
+                    &spec,
+                    ptr::null_mut(),
+                    ptr::null_mut(),
+                );
+                if status != 0 {
+                    return Err(HotKeyError::System(status));
+                }
+                self.handler_installed = true;
+            }
+
+            let mut hotkey_ref = ptr::null_mut();
+            let status = RegisterEventHotKey(
+                key_code.0 as u32,
+                carbon_modifiers(mask),
+                EventHotKeyID {
+                    signature: SIGNATURE,
+                    id,
+                },
+                GetApplicationEventTarget(),
+                0,
+                &mut hotkey_ref,
+            );
+            match status {
+                0 => {
+                    self.refs.push((id, hotkey_ref));
+                    Ok(())
+                }
+                EVENT_HOT_KEY_EXISTS_ERR => Err(HotKeyError::InUse),
+                status => Err(HotKeyError::System(status)),
+            }
+        }
+    }
+
+    fn unregister(&mut self, id: u32) {
+        if let Some(index) = self.refs.iter().position(|(other, _)| *other == id) {
+            let (_, hotkey_ref) = self.refs.remove(index);
+            unsafe {
+                UnregisterEventHotKey(hotkey_ref);
+            }
+        }
+    }
+}
+
+/// `Shortcut::mask()` modifiers as Carbon modifiers.
+fn carbon_modifiers(mask: NSUInteger) -> u32 {
+    [
+        (1 << 20, 1 << 8),  // cmdKey
+        (1 << 17, 1 << 9),  // shiftKey
+        (1 << 16, 1 << 10), // alphaLock
+        (1 << 19, 1 << 11), // optionKey
+        (1 << 18, 1 << 12), // controlKey
+    ]
+    .iter()
+    .filter(|(ns, _)| mask & ns != 0)
+    .fold(0, |modifiers, (_, carbon)| modifiers | carbon)
+}
+
+extern "C" fn hotkey_pressed(
+    _next: EventHandlerCallRef,
+    event: EventRef,
+    _user_data: *mut c_void,
+) -> OSStatus {
+    let mut hotkey_id = EventHotKeyID::default();
+    let status = unsafe {
+        GetEventParameter(
+            event,
+            K_EVENT_PARAM_DIRECT_OBJECT,
+            TYPE_EVENT_HOT_KEY_ID,
+            ptr::null_mut(),
+            std::mem::size_of::<EventHotKeyID>(),
+            ptr::null_mut(),
+            &mut hotkey_id as *mut EventHotKeyID as *mut c_void,
+        )
+    };
+    // Hotkeys of other handlers in the app are passed on
+    if status != 0 || hotkey_id.signature != SIGNATURE {
+        return EVENT_NOT_HANDLED_ERR;
+    }
+
+    // The registry isn't borrowed while the action runs, so it can (un)register hotkeys
+    match hotkey_action(hotkey_id.id) {
+        Some(action) => {
+            action.perform();
+            0
+        }
+        None => EVENT_NOT_HANDLED_ERR,
+    }
+}
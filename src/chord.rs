@@ -0,0 +1,255 @@
+use std::time::{Duration, Instant};
+
+#[cfg(target_os = "macos")]
+use crate::{Action, MenuBar};
+use crate::{ChordShortcut, Key, Shortcut};
+
+// ----------------------------------------------------------------------------
+
+/// Matches key presses against chords, e.g. ⌘K ⌘C, and reports the bound value.
+///
+/// Key presses aren't seen by menus, so the app feeds them in from its key event handling, see
+/// `press(..)`. A `ChordMatcher<Action>` can `dispatch(..)` the matched action instead.
+pub struct ChordMatcher<T> {
+    bindings: Vec<(ChordShortcut, T)>,
+    pending: Vec<Shortcut>,
+    last_press: Option<Instant>,
+    timeout: Duration,
+}
+
+/// The result of a key press, see `ChordMatcher::press(..)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChordEvent<T> {
+    /// The key press completed a chord.
+    Matched(T),
+    /// The key press started or continued a chord, the next key press is needed.
+    Pending,
+    /// A pending chord was cancelled by Escape or a key press that matches no chord. The key press
+    /// was consumed.
+    Cancelled,
+    /// The key press isn't part of a chord and should be handled as usual.
+    Unmatched,
+}
+
+impl<T> Default for ChordMatcher<T> {
+    fn default() -> Self {
+        Self {
+            bindings: Vec::new(),
+            pending: Vec::new(),
+            last_press: None,
+            timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl<T: Clone> ChordMatcher<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// How long to wait for the next key of a chord, 2 seconds by default.
+    pub fn timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    pub fn bind(&mut self, chord: ChordShortcut, value: T) {
+        self.bindings.push((chord, value));
+    }
+
+    /// The keys pressed so far of a pending chord, empty if there's none.
+    pub fn pending(&self) -> &[Shortcut] {
+        &self.pending
+    }
+
+    /// The pending keys as shown in menus, e.g. "⌘K", to show that the app waits for the next key.
+    pub fn pending_symbols(&self) -> String {
+        let symbols: Vec<_> = self.pending.iter().map(Shortcut::symbols).collect();
+        symbols.join(" ")
+    }
+
+    pub fn cancel(&mut self) {
+        self.pending.clear();
+        self.last_press = None;
+    }
+
+    /// Handles a key press at `now`.
+    ///
+    /// A pending chord is dropped if `timeout` passed since its last key. A chord that is
+    /// completed matches right away, even if a longer chord starts with it.
+    pub fn press(&mut self, shortcut: &Shortcut, now: Instant) -> ChordEvent<T> {
+        let timed_out = self
+            .last_press
+            .is_some_and(|last_press| now.duration_since(last_press) > self.timeout);
+        if timed_out {
+            self.cancel();
+        }
+
+        let was_pending = !self.pending.is_empty();
+        if was_pending && is_escape(shortcut) {
+            self.cancel();
+            return ChordEvent::Cancelled;
+        }
+
+        let mut keys = self.pending.clone();
+        keys.push(shortcut.clone());
+
+        let matched = self
+            .bindings
+            .iter()
+            .find(|(chord, _)| chord.keys().len() == keys.len() && chord.starts_with(&keys));
+        if let Some((_, value)) = matched {
+            let value = value.clone();
+            self.cancel();
+            return ChordEvent::Matched(value);
+        }
+
+        if self
+            .bindings
+            .iter()
+            .any(|(chord, _)| chord.starts_with(&keys))
+        {
+            self.pending = keys;
+            self.last_press = Some(now);
+            return ChordEvent::Pending;
+        }
+
+        self.cancel();
+        if was_pending {
+            ChordEvent::Cancelled
+        } else {
+            ChordEvent::Unmatched
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl ChordMatcher<Action> {
+    /// The chords of all items of `menubar` that have one, bound to the items' actions. Lazy
+    /// submenus aren't included.
+    pub fn from_menubar(menubar: &MenuBar) -> Self {
+        let mut matcher = Self::new();
+        for menu in menubar.main_menu.iter().chain(menubar.menus.iter()) {
+            menu.chord_entries(&mut matcher);
+        }
+
+        matcher
+    }
+
+    /// Handles a key press now and performs the matched action.
+    pub fn dispatch(&mut self, shortcut: &Shortcut) -> ChordEvent<Action> {
+        let event = self.press(shortcut, Instant::now());
+        if let ChordEvent::Matched(ref action) = event {
+            action.perform();
+        }
+
+        event
+    }
+}
+
+fn is_escape(shortcut: &Shortcut) -> bool {
+    *shortcut == Shortcut::new(Key::Other(String::from("\u{1b}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(key: Key) -> Shortcut {
+        Shortcut::new(key).command(true)
+    }
+
+    fn matcher() -> ChordMatcher<&'static str> {
+        let mut matcher = ChordMatcher::new().timeout(Duration::from_secs(1));
+        matcher.bind(
+            ChordShortcut::new(command(Key::K)).then(command(Key::C)),
+            "comment",
+        );
+        matcher.bind(
+            ChordShortcut::new(command(Key::K)).then(command(Key::U)),
+            "uncomment",
+        );
+        matcher
+    }
+
+    #[test]
+    fn second_key_completes_chord() {
+        let mut matcher = matcher();
+        let start = Instant::now();
+
+        assert_eq!(matcher.press(&command(Key::K), start), ChordEvent::Pending);
+        assert_eq!(matcher.pending(), [command(Key::K)]);
+        assert_eq!(matcher.pending_symbols(), "⌘K");
+
+        let now = start + Duration::from_millis(500);
+        assert_eq!(
+            matcher.press(&command(Key::C), now),
+            ChordEvent::Matched("comment")
+        );
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn timeout_drops_pending_keys() {
+        let mut matcher = matcher();
+        let start = Instant::now();
+        matcher.press(&command(Key::K), start);
+
+        let now = start + Duration::from_millis(1500);
+        assert_eq!(matcher.press(&command(Key::C), now), ChordEvent::Unmatched);
+        assert!(matcher.pending().is_empty());
+
+        assert_eq!(matcher.press(&command(Key::K), now), ChordEvent::Pending);
+    }
+
+    #[test]
+    fn escape_cancels_pending_chord() {
+        let mut matcher = matcher();
+        let start = Instant::now();
+        let escape = Shortcut::new(Key::Other(String::from("\u{1b}")));
+
+        assert_eq!(matcher.press(&escape, start), ChordEvent::Unmatched);
+
+        matcher.press(&command(Key::K), start);
+        assert_eq!(matcher.press(&escape, start), ChordEvent::Cancelled);
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn unknown_second_key_cancels() {
+        let mut matcher = matcher();
+        let start = Instant::now();
+        matcher.press(&command(Key::K), start);
+
+        assert_eq!(
+            matcher.press(&command(Key::X), start),
+            ChordEvent::Cancelled
+        );
+        assert!(matcher.pending().is_empty());
+    }
+
+    #[test]
+    fn lone_key_is_unmatched() {
+        let mut matcher = matcher();
+
+        assert_eq!(
+            matcher.press(&command(Key::C), Instant::now()),
+            ChordEvent::Unmatched
+        );
+    }
+
+    #[test]
+    fn shorter_chord_wins() {
+        let mut matcher = matcher();
+        matcher.bind(ChordShortcut::new(command(Key::K)), "kill");
+        let start = Instant::now();
+
+        assert_eq!(
+            matcher.press(&command(Key::K), start),
+            ChordEvent::Matched("kill")
+        );
+        assert_eq!(
+            matcher.press(&command(Key::C), start),
+            ChordEvent::Unmatched
+        );
+    }
+}
@@ -0,0 +1,265 @@
+#[cfg(target_os = "macos")]
+use crate::Action;
+use crate::{NSUInteger, Shortcut};
+
+// ----------------------------------------------------------------------------
+
+/// A shortcut that won't work as expected, see `MenuBar::shortcut_conflicts()`.
+///
+/// Paths are the titles from the top-level menu down to the item, e.g. `["File", "Export"]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShortcutConflict {
+    /// Several items use the same shortcut, only the first one is chosen by it.
+    Duplicate {
+        shortcut: String,
+        paths: Vec<Vec<String>>,
+    },
+    /// An item uses a shortcut the system handles before the app sees it.
+    Reserved {
+        shortcut: String,
+        path: Vec<String>,
+        /// What the system uses the shortcut for, e.g. "Quit".
+        reserved_for: &'static str,
+    },
+}
+
+/// A shortcut of a realized item, found while walking the menus.
+#[cfg(target_os = "macos")]
+pub(crate) struct ShortcutEntry {
+    pub(crate) path: Vec<String>,
+    pub(crate) key: String,
+    pub(crate) mask: NSUInteger,
+    pub(crate) action: Action,
+}
+
+const CAPSLOCK: NSUInteger = 1 << 16;
+const SHIFT: NSUInteger = 1 << 17;
+const CONTROL: NSUInteger = 1 << 18;
+const OPTION: NSUInteger = 1 << 19;
+const COMMAND: NSUInteger = 1 << 20;
+
+struct ReservedShortcut {
+    key: &'static str,
+    mask: NSUInteger,
+    reserved_for: &'static str,
+}
+
+const RESERVED: &[ReservedShortcut] = &[
+    ReservedShortcut {
+        key: "q",
+        mask: COMMAND,
+        reserved_for: "Quit",
+    },
+    ReservedShortcut {
+        key: "h",
+        mask: COMMAND,
+        reserved_for: "Hide",
+    },
+    ReservedShortcut {
+        key: "h",
+        mask: COMMAND | OPTION,
+        reserved_for: "Hide Others",
+    },
+    ReservedShortcut {
+        key: "\t",
+        mask: COMMAND,
+        reserved_for: "App Switcher",
+    },
+    ReservedShortcut {
+        key: "\t",
+        mask: COMMAND | SHIFT,
+        reserved_for: "App Switcher",
+    },
+    ReservedShortcut {
+        key: " ",
+        mask: COMMAND,
+        reserved_for: "Spotlight",
+    },
+    ReservedShortcut {
+        key: " ",
+        mask: CONTROL,
+        reserved_for: "Input Sources",
+    },
+    ReservedShortcut {
+        key: "\u{1b}",
+        mask: COMMAND | OPTION,
+        reserved_for: "Force Quit",
+    },
+    ReservedShortcut {
+        key: "q",
+        mask: COMMAND | CONTROL,
+        reserved_for: "Lock Screen",
+    },
+    ReservedShortcut {
+        key: "3",
+        mask: COMMAND | SHIFT,
+        reserved_for: "Screenshot",
+    },
+    ReservedShortcut {
+        key: "4",
+        mask: COMMAND | SHIFT,
+        reserved_for: "Screenshot",
+    },
+    ReservedShortcut {
+        key: "5",
+        mask: COMMAND | SHIFT,
+        reserved_for: "Screenshot",
+    },
+];
+
+/// Finds duplicate and reserved shortcuts among `entries`, in the order they first appear.
+#[cfg(target_os = "macos")]
+pub(crate) fn find_conflicts(entries: &[ShortcutEntry]) -> Vec<ShortcutConflict> {
+    let normalized: Vec<_> = entries
+        .iter()
+        .map(|entry| normalize(&entry.key, entry.mask))
+        .collect();
+
+    let mut conflicts = Vec::new();
+    let mut reported = Vec::new();
+
+    for (index, entry) in entries.iter().enumerate() {
+        let (ref key, mask) = normalized[index];
+        if key.is_empty() {
+            continue;
+        }
+
+        if let Some(reserved) = find_reserved(key, mask) {
+            if !is_owner(reserved, &entry.action) {
+                conflicts.push(ShortcutConflict::Reserved {
+                    shortcut: display(key, mask),
+                    path: entry.path.clone(),
+                    reserved_for: reserved.reserved_for,
+                });
+            }
+        }
+
+        if reported.contains(&normalized[index]) {
+            continue;
+        }
+        let paths: Vec<_> = entries
+            .iter()
+            .zip(normalized.iter())
+            .filter(|(_, other)| **other == normalized[index])
+            .map(|(entry, _)| entry.path.clone())
+            .collect();
+        if paths.len() > 1 {
+            reported.push(normalized[index].clone());
+            conflicts.push(ShortcutConflict::Duplicate {
+                shortcut: display(key, mask),
+                paths,
+            });
+        }
+    }
+
+    conflicts
+}
+
+/// What the system uses `shortcut` for, `None` if it isn't reserved.
+pub(crate) fn reserved_for(shortcut: &Shortcut) -> Option<&'static str> {
+    let (key, mask) = normalize(&shortcut.key.to_string(), shortcut.mask());
+    find_reserved(&key, mask).map(|reserved| reserved.reserved_for)
+}
+
+/// Whether `action` is the standard item that is meant to use a reserved shortcut, e.g. "Quit"
+/// for ⌘Q.
+#[cfg(target_os = "macos")]
+fn is_owner(reserved: &ReservedShortcut, action: &Action) -> bool {
+    matches!(
+        (reserved.reserved_for, action),
+        ("Quit", Action::Quit) | ("Hide", Action::Hide) | ("Hide Others", Action::HideOthers)
+    )
+}
+
+fn find_reserved(key: &str, mask: NSUInteger) -> Option<&'static ReservedShortcut> {
+    RESERVED
+        .iter()
+        .find(|reserved| reserved.key == key && reserved.mask == mask)
+}
+
+/// The key and modifiers AppKit matches a key equivalent with: uppercase keys imply shift, caps
+/// lock is ignored.
+pub(crate) fn normalize(key: &str, mask: NSUInteger) -> (String, NSUInteger) {
+    let mask = mask & !CAPSLOCK;
+    let lowercase = key.to_lowercase();
+
+    if lowercase != key {
+        (lowercase, mask | SHIFT)
+    } else {
+        (key.to_string(), mask)
+    }
+}
+
+/// A shortcut as shown in menus, e.g. "⇧⌘E".
+pub(crate) fn display(key: &str, mask: NSUInteger) -> String {
+    let mut text = String::new();
+
+    for (modifier, symbol) in [(CONTROL, '⌃'), (OPTION, '⌥'), (SHIFT, '⇧'), (COMMAND, '⌘')]
+    {
+        if mask & modifier != 0 {
+            text.push(symbol);
+        }
+    }
+
+    let name = match key {
+        "\t" => String::from("Tab"),
+        "\r" => String::from("Return"),
+        " " => String::from("Space"),
+        "\u{1b}" => String::from("Esc"),
+        "\x7F" => String::from("Delete"),
+        "\x08" => String::from("Backspace"),
+        "\u{F700}" => String::from("↑"),
+        "\u{F701}" => String::from("↓"),
+        "\u{F702}" => String::from("←"),
+        "\u{F703}" => String::from("→"),
+        key => match key.chars().next() {
+            Some(c @ '\u{F704}'..='\u{F726}') if key.chars().count() == 1 => {
+                format!("F{}", c as u32 - 0xF704 + 1)
+            }
+            _ => key.to_uppercase(),
+        },
+    };
+    text.push_str(&name);
+
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_drops_capslock_and_shifts_uppercase() {
+        assert_eq!(
+            normalize("e", CAPSLOCK | COMMAND),
+            (String::from("e"), COMMAND)
+        );
+        assert_eq!(
+            normalize("E", COMMAND),
+            (String::from("e"), SHIFT | COMMAND)
+        );
+    }
+
+    #[test]
+    fn display_orders_modifiers_like_appkit() {
+        assert_eq!(display("e", 0x1E0000), "⌃⌥⇧⌘E");
+        assert_eq!(display("\u{F705}", COMMAND), "⌘F2");
+        assert_eq!(display("\u{1b}", OPTION | COMMAND), "⌥⌘Esc");
+    }
+
+    #[test]
+    fn reserved_shortcuts() {
+        let shortcut = |mask, key: &str| Shortcut::from_mask_and_chars(mask, key).unwrap();
+
+        assert_eq!(reserved_for(&shortcut(COMMAND, "q")), Some("Quit"));
+        assert_eq!(
+            reserved_for(&shortcut(CAPSLOCK | COMMAND, "q")),
+            Some("Quit")
+        );
+        assert_eq!(reserved_for(&shortcut(COMMAND, "Q")), None);
+        assert_eq!(
+            reserved_for(&shortcut(OPTION | COMMAND, "h")),
+            Some("Hide Others")
+        );
+    }
+}
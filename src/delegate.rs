@@ -0,0 +1,178 @@
+use std::sync::Arc;
+
+use icrate::{AppKit::NSMenu, Foundation::NSString};
+use libc::c_void;
+use objc2::{
+    class,
+    declare::ClassBuilder,
+    ffi, msg_send,
+    runtime::{AnyClass, NSObject, Sel},
+    sel,
+};
+use once_cell::sync::Lazy;
+
+use crate::{
+    direction, id,
+    item::{load, validate_item},
+    palette::Palette,
+    MenuItem,
+};
+
+// ----------------------------------------------------------------------------
+
+type Callback = Arc<dyn Fn() + Send + Sync + 'static>;
+type HighlightCallback = Arc<dyn Fn(Option<&str>) + Send + Sync + 'static>;
+type UpdateCallback = Arc<dyn Fn() -> Option<Vec<MenuItem>> + Send + Sync + 'static>;
+
+/// Rust closures called by the `NSMenuDelegate` of a realized `Menu`.
+#[derive(Clone, Default)]
+pub(crate) struct MenuEvents {
+    pub(crate) will_open: Option<Callback>,
+    pub(crate) did_close: Option<Callback>,
+    pub(crate) highlight: Option<HighlightCallback>,
+    pub(crate) needs_update: Option<UpdateCallback>,
+    pub(crate) palette: Option<Palette>,
+}
+
+impl MenuEvents {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.will_open.is_none()
+            && self.did_close.is_none()
+            && self.highlight.is_none()
+            && self.needs_update.is_none()
+            && self.palette.is_none()
+    }
+
+    /// Sets a delegate on `menu` that dispatches to these closures.
+    ///
+    /// The delegate also runs the validators of the menu's items every time it's about to open.
+    ///
+    /// `NSMenu` doesn't retain its delegate, so the delegate is attached to `menu` as an associated
+    /// object and lives as long as the menu does.
+    pub(crate) fn attach(&self, menu: &NSMenu) {
+        let events = Box::new(self.clone());
+        let ptr = Box::into_raw(events);
+
+        unsafe {
+            let delegate: id = msg_send![register_menu_delegate_class(), new];
+            (&mut *delegate).set_ivar(EVENTS_PTR, ptr as usize);
+
+            let _: () = msg_send![menu, setDelegate: delegate];
+            ffi::objc_setAssociatedObject(
+                menu as *const NSMenu as *mut ffi::objc_object,
+                &DELEGATE_KEY as *const u8 as *const c_void,
+                delegate as *mut ffi::objc_object,
+                ffi::OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+            );
+            let _: () = msg_send![delegate, release];
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+static EVENTS_PTR: &str = "cacaoMenuEventsPtr";
+static DELEGATE_KEY: u8 = 0;
+
+extern "C" fn dealloc_cacao_menu_delegate(this: &NSObject, _: Sel) {
+    unsafe {
+        let ptr: usize = *this.ivar(EVENTS_PTR);
+        let obj = ptr as *mut MenuEvents;
+
+        if !obj.is_null() {
+            let _events = Box::from_raw(obj);
+        }
+
+        let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+}
+
+extern "C" fn menu_will_open(this: &NSObject, _: Sel, _menu: id) {
+    let events = load::<MenuEvents>(this, EVENTS_PTR);
+    if let Some(ref will_open) = events.will_open {
+        will_open();
+    }
+}
+
+extern "C" fn menu_did_close(this: &NSObject, _: Sel, _menu: id) {
+    let events = load::<MenuEvents>(this, EVENTS_PTR);
+    if let Some(ref did_close) = events.did_close {
+        did_close();
+    }
+}
+
+extern "C" fn menu_will_highlight_item(this: &NSObject, _: Sel, _menu: id, item: id) {
+    let events = load::<MenuEvents>(this, EVENTS_PTR);
+    if let Some(ref highlight) = events.highlight {
+        let id = if item.is_null() {
+            None
+        } else {
+            let identifier: Option<&NSString> = unsafe { msg_send![item, identifier] };
+            identifier.map(|identifier| identifier.to_string())
+        };
+        highlight(id.as_deref());
+    }
+}
+
+extern "C" fn menu_needs_update(this: &NSObject, _: Sel, menu: &NSMenu) {
+    let events = load::<MenuEvents>(this, EVENTS_PTR);
+    if let Some(ref needs_update) = events.needs_update {
+        if let Some(items) = needs_update() {
+            unsafe {
+                menu.removeAllItems();
+                direction::realize_in(menu, || {
+                    for item in items.iter() {
+                        item.add_to(menu);
+                    }
+                });
+            }
+        }
+    }
+
+    unsafe {
+        for item in menu.itemArray().iter() {
+            validate_item(item);
+        }
+    }
+
+    if let Some(ref palette) = events.palette {
+        palette.apply(menu);
+    }
+}
+
+fn register_menu_delegate_class() -> &'static AnyClass {
+    static CLASS: Lazy<&'static AnyClass> = Lazy::new(|| unsafe {
+        let mut builder = ClassBuilder::new("CacaoMenuDelegate", class!(NSObject)).unwrap();
+
+        builder.add_ivar::<usize>(EVENTS_PTR);
+
+        builder.add_method(
+            sel!(dealloc),
+            dealloc_cacao_menu_delegate as unsafe extern "C" fn(_, _),
+        );
+
+        builder.add_method(
+            sel!(menuWillOpen:),
+            menu_will_open as unsafe extern "C" fn(_, _, id),
+        );
+
+        builder.add_method(
+            sel!(menuDidClose:),
+            menu_did_close as unsafe extern "C" fn(_, _, id),
+        );
+
+        builder.add_method(
+            sel!(menu:willHighlightItem:),
+            menu_will_highlight_item as unsafe extern "C" fn(_, _, id, id),
+        );
+
+        builder.add_method(
+            sel!(menuNeedsUpdate:),
+            menu_needs_update as unsafe extern "C" fn(_, _, _),
+        );
+
+        builder.register()
+    });
+
+    &CLASS
+}
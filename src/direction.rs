@@ -0,0 +1,87 @@
+use std::cell::RefCell;
+
+use icrate::AppKit::{
+    NSApplication, NSMenu, NSUserInterfaceLayoutDirection,
+    NSUserInterfaceLayoutDirectionLeftToRight, NSUserInterfaceLayoutDirectionRightToLeft,
+};
+
+// ----------------------------------------------------------------------------
+
+/// The layout direction of a menu, see `Menu::layout_direction(..)`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LayoutDirection {
+    /// The direction of the parent menu, or of the app for top-level menus.
+    #[default]
+    Inherit,
+    LeftToRight,
+    /// Items are laid out right to left, e.g. for Arabic and Hebrew.
+    RightToLeft,
+}
+
+thread_local! {
+    /// Directions of the menus being realized, innermost last.
+    static REALIZING: RefCell<Vec<LayoutDirection>> = const { RefCell::new(Vec::new()) };
+}
+
+impl LayoutDirection {
+    /// Resolves `Inherit` to the direction of the menu being realized, or of the app.
+    fn resolve(self) -> Self {
+        if self != LayoutDirection::Inherit {
+            return self;
+        }
+
+        let parent = REALIZING.with(|realizing| realizing.borrow().last().copied());
+        parent.unwrap_or_else(|| unsafe {
+            let app = NSApplication::sharedApplication();
+            Self::from_objc(app.userInterfaceLayoutDirection())
+        })
+    }
+
+    fn from_objc(direction: NSUserInterfaceLayoutDirection) -> Self {
+        if direction == NSUserInterfaceLayoutDirectionRightToLeft {
+            LayoutDirection::RightToLeft
+        } else {
+            LayoutDirection::LeftToRight
+        }
+    }
+
+    fn to_objc(self) -> NSUserInterfaceLayoutDirection {
+        match self {
+            LayoutDirection::RightToLeft => NSUserInterfaceLayoutDirectionRightToLeft,
+            _ => NSUserInterfaceLayoutDirectionLeftToRight,
+        }
+    }
+}
+
+/// Realizes a menu with `direction` in `f`, menus and images realized in `f` inherit it.
+pub(crate) fn realize_with<R>(direction: LayoutDirection, f: impl FnOnce() -> R) -> R {
+    let direction = direction.resolve();
+
+    REALIZING.with(|realizing| realizing.borrow_mut().push(direction));
+    let result = f();
+    REALIZING.with(|realizing| realizing.borrow_mut().pop());
+
+    result
+}
+
+/// Realizes items added to the already realized `menu` in `f`, e.g. by a lazy submenu.
+pub(crate) fn realize_in<R>(menu: &NSMenu, f: impl FnOnce() -> R) -> R {
+    let direction = unsafe { LayoutDirection::from_objc(menu.userInterfaceLayoutDirection()) };
+    realize_with(direction, f)
+}
+
+/// Applies the direction being realized to `menu`.
+pub(crate) fn apply(menu: &NSMenu) {
+    unsafe {
+        menu.setUserInterfaceLayoutDirection(current().to_objc());
+    }
+}
+
+/// Whether the menu being realized is laid out right to left, see `Image::mirror_in_rtl(..)`.
+pub(crate) fn is_rtl() -> bool {
+    current() == LayoutDirection::RightToLeft
+}
+
+fn current() -> LayoutDirection {
+    LayoutDirection::Inherit.resolve()
+}
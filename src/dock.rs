@@ -0,0 +1,93 @@
+use std::{cell::RefCell, rc::Rc};
+
+use icrate::AppKit::{NSApplication, NSMenu};
+use objc2::{
+    ffi, msg_send,
+    rc::Id,
+    runtime::{AnyClass, NSObject, Sel},
+    sel,
+};
+
+use crate::{id, nil, Menu};
+
+// ----------------------------------------------------------------------------
+
+thread_local! {
+    static DOCK_MENU: RefCell<Option<Rc<dyn Fn() -> Menu>>> = const { RefCell::new(None) };
+    static INSTALLED_ON: RefCell<Option<*const AnyClass>> = const { RefCell::new(None) };
+}
+
+/// Sets the menu shown when right-clicking the app's Dock tile, `None` removes it.
+///
+/// # Warning
+/// - This will panic if no shared application exists.
+/// - Does nothing if the application has no delegate yet, call it after the application is done
+///   initializing.
+pub fn set_dock_menu(menu: Option<Menu>) {
+    match menu {
+        Some(menu) => set_dock_menu_provider(move || menu.clone()),
+        None => DOCK_MENU.with(|dock_menu| *dock_menu.borrow_mut() = None),
+    }
+}
+
+/// Sets a closure that builds the Dock menu every time the user opens it.
+///
+/// Useful for menus that change often, e.g. a list of recent projects.
+///
+/// # Info
+/// `applicationDockMenu:` is added to the class of the current application delegate, so there's
+/// no need to own the delegate. Any existing implementation on that class is replaced.
+///
+/// # Warning
+/// - This will panic if no shared application exists.
+/// - Does nothing if the application has no delegate yet, call it after the application is done
+///   initializing.
+pub fn set_dock_menu_provider<F>(provider: F)
+where
+    F: Fn() -> Menu + 'static,
+{
+    DOCK_MENU.with(|dock_menu| *dock_menu.borrow_mut() = Some(Rc::new(provider)));
+
+    unsafe {
+        let app = NSApplication::sharedApplication();
+        let delegate: id = msg_send![&*app, delegate];
+        if delegate.is_null() {
+            return;
+        }
+
+        install_dock_menu_method((&*delegate).class());
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+extern "C" fn application_dock_menu(_this: &NSObject, _: Sel, _sender: id) -> *mut NSMenu {
+    let provider = DOCK_MENU.with(|dock_menu| dock_menu.borrow().clone());
+
+    match provider {
+        Some(provider) => Id::autorelease_return(provider().to_objc()),
+        None => nil as *mut NSMenu,
+    }
+}
+
+unsafe fn install_dock_menu_method(class: &AnyClass) {
+    let class = class as *const AnyClass;
+    let installed = INSTALLED_ON.with(|installed| *installed.borrow() == Some(class));
+    if installed {
+        return;
+    }
+
+    type DockMenuImp = unsafe extern "C" fn(&NSObject, Sel, id) -> *mut NSMenu;
+    let imp: DockMenuImp = application_dock_menu;
+
+    ffi::class_replaceMethod(
+        class as *mut ffi::objc_class,
+        sel!(applicationDockMenu:).as_ptr(),
+        Some(std::mem::transmute::<DockMenuImp, unsafe extern "C" fn()>(
+            imp,
+        )),
+        c"@@:@".as_ptr(),
+    );
+
+    INSTALLED_ON.with(|installed| *installed.borrow_mut() = Some(class));
+}
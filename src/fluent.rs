@@ -0,0 +1,166 @@
+use std::{error::Error, fmt};
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+use crate::Localizer;
+
+// ----------------------------------------------------------------------------
+
+/// A `Localizer` for Fluent (`.ftl`) translations of one locale, requires the `fluent` feature.
+///
+/// Attributes aren't used, message values are the titles:
+/// ```ftl
+/// file-menu = Datei
+/// open-recent = Zuletzt geöffnet ({ $count })
+/// ```
+pub struct FluentLocalizer {
+    bundle: FluentBundle<FluentResource>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FluentError {
+    InvalidLocale(String),
+    /// The `.ftl` source has syntax errors, the messages without errors were still added.
+    Syntax(Vec<String>),
+    /// A message is defined more than once, the first definition is used.
+    DuplicateMessage(String),
+}
+
+impl FluentLocalizer {
+    /// An empty localizer for `locale`, e.g. "de-DE".
+    pub fn new(locale: &str) -> Result<Self, FluentError> {
+        let locale: LanguageIdentifier = locale
+            .parse()
+            .map_err(|_| FluentError::InvalidLocale(locale.to_string()))?;
+
+        let mut bundle = FluentBundle::new(vec![locale]);
+        // Unicode isolation marks show up as boxes in some menu fonts
+        bundle.set_use_isolating(false);
+
+        Ok(Self { bundle })
+    }
+
+    /// Adds the messages of `.ftl` source.
+    pub fn add_ftl(&mut self, source: &str) -> Result<(), FluentError> {
+        let (resource, syntax_errors) = match FluentResource::try_new(source.to_string()) {
+            Ok(resource) => (resource, Vec::new()),
+            Err((resource, errors)) => {
+                let errors = errors.iter().map(|err| err.to_string()).collect();
+                (resource, errors)
+            }
+        };
+
+        let duplicate = self.bundle.add_resource(resource).err().and_then(|errors| {
+            errors.into_iter().find_map(|err| match err {
+                fluent_bundle::FluentError::Overriding { id, .. } => Some(id),
+                _ => None,
+            })
+        });
+
+        match duplicate {
+            _ if !syntax_errors.is_empty() => Err(FluentError::Syntax(syntax_errors)),
+            Some(id) => Err(FluentError::DuplicateMessage(id)),
+            None => Ok(()),
+        }
+    }
+}
+
+impl Localizer for FluentLocalizer {
+    fn localize(&self, key: &str, args: &[(String, String)]) -> Option<String> {
+        let pattern = self.bundle.get_message(key)?.value()?;
+
+        // Numbers are passed as numbers, so they select plural variants
+        let mut fluent_args = FluentArgs::new();
+        for (name, value) in args {
+            fluent_args.set(name.as_str(), FluentValue::try_number(value));
+        }
+
+        let mut errors = Vec::new();
+        let text = self
+            .bundle
+            .format_pattern(pattern, Some(&fluent_args), &mut errors);
+
+        Some(text.into_owned())
+    }
+}
+
+impl fmt::Display for FluentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FluentError::InvalidLocale(locale) => write!(f, "invalid locale '{}'", locale),
+            FluentError::Syntax(errors) => write!(f, "{}", errors.join(", ")),
+            FluentError::DuplicateMessage(id) => write!(f, "message '{}' is defined twice", id),
+        }
+    }
+}
+
+impl Error for FluentError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn localizer(ftl: &str) -> FluentLocalizer {
+        let mut localizer = FluentLocalizer::new("en-US").unwrap();
+        localizer.add_ftl(ftl).unwrap();
+        localizer
+    }
+
+    fn args(args: &[(&str, &str)]) -> Vec<(String, String)> {
+        args.iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn localizes_messages() {
+        let localizer = localizer("file-menu = File\nopen = Open { $name }");
+
+        assert_eq!(
+            localizer.localize("file-menu", &[]).as_deref(),
+            Some("File")
+        );
+        assert_eq!(
+            localizer
+                .localize("open", &args(&[("name", "notes.txt")]))
+                .as_deref(),
+            Some("Open notes.txt")
+        );
+        assert_eq!(localizer.localize("missing", &[]), None);
+    }
+
+    #[test]
+    fn numeric_args_select_plurals() {
+        let localizer =
+            localizer("files = { $count ->\n    [one] One file\n   *[other] { $count } files\n}");
+        let files = |count| localizer.localize("files", &args(&[("count", count)]));
+
+        assert_eq!(files("1").as_deref(), Some("One file"));
+        assert_eq!(files("3").as_deref(), Some("3 files"));
+        assert_eq!(files("2.50").as_deref(), Some("2.50 files"));
+        assert_eq!(files("many").as_deref(), Some("many files"));
+    }
+
+    #[test]
+    fn reports_errors() {
+        assert_eq!(
+            FluentLocalizer::new("not a locale!").err(),
+            Some(FluentError::InvalidLocale(String::from("not a locale!")))
+        );
+
+        let mut localizer = localizer("file-menu = File");
+        assert_eq!(
+            localizer.add_ftl("file-menu = Datei"),
+            Err(FluentError::DuplicateMessage(String::from("file-menu")))
+        );
+        assert!(matches!(
+            localizer.add_ftl("edit-menu = Edit\n= broken"),
+            Err(FluentError::Syntax(_))
+        ));
+        assert_eq!(
+            localizer.localize("edit-menu", &[]).as_deref(),
+            Some("Edit")
+        );
+    }
+}
@@ -0,0 +1,342 @@
+#[cfg(target_os = "macos")]
+use std::cell::RefCell;
+
+#[cfg(target_os = "macos")]
+use crate::carbon::CarbonHotKeys;
+use crate::conflicts::{normalize, reserved_for};
+#[cfg(target_os = "macos")]
+use crate::Action;
+use crate::{KeyCode, Layout, NSUInteger, Shortcut};
+
+// ----------------------------------------------------------------------------
+
+/// A registered global hotkey, see `register_hotkey(..)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HotKeyId(u32);
+
+/// Why a global hotkey couldn't be registered.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HotKeyError {
+    /// The shortcut is already registered by this app.
+    Duplicate(HotKeyId),
+    /// The shortcut is reserved by the system, e.g. ⌘Space for Spotlight.
+    Reserved(&'static str),
+    /// The key has no physical key on the hotkey layout, see `set_hotkey_layout(..)`.
+    UnknownKey,
+    /// Another app already registered the shortcut.
+    InUse,
+    /// Registering failed with this `OSStatus`.
+    System(i32),
+}
+
+#[cfg(target_os = "macos")]
+thread_local! {
+    static HOTKEYS: RefCell<HotKeyRegistry<CarbonHotKeys, Action>> =
+        RefCell::new(HotKeyRegistry::new(CarbonHotKeys::default()));
+}
+
+/// Registers a shortcut that performs `action` even while the app is in the background, e.g.
+/// ⌥Space for a launcher.
+///
+/// # Info
+/// Hotkeys are physical keys, the shortcut's character is looked up on the hotkey layout, see
+/// `set_hotkey_layout(..)`. Actions are performed on the main thread.
+///
+/// # Warning
+/// Must be called on the main thread.
+#[cfg(target_os = "macos")]
+pub fn register_hotkey(shortcut: Shortcut, action: Action) -> Result<HotKeyId, HotKeyError> {
+    HOTKEYS.with(|hotkeys| hotkeys.borrow_mut().register(shortcut, action))
+}
+
+/// Unregisters a hotkey, returns `false` if it wasn't registered.
+#[cfg(target_os = "macos")]
+pub fn unregister_hotkey(id: HotKeyId) -> bool {
+    HOTKEYS.with(|hotkeys| hotkeys.borrow_mut().unregister(id))
+}
+
+/// The registered hotkeys, in the order they were registered.
+#[cfg(target_os = "macos")]
+pub fn registered_hotkeys() -> Vec<(HotKeyId, Shortcut)> {
+    HOTKEYS.with(|hotkeys| hotkeys.borrow().registered())
+}
+
+/// The layout used to find the physical key of hotkeys registered from now on, `Layout::Us` by
+/// default.
+#[cfg(target_os = "macos")]
+pub fn set_hotkey_layout(layout: Layout) {
+    HOTKEYS.with(|hotkeys| hotkeys.borrow_mut().layout = layout);
+}
+
+/// The action of the hotkey with the backend id `id`, `None` if it isn't registered.
+#[cfg(target_os = "macos")]
+pub(crate) fn hotkey_action(id: u32) -> Option<Action> {
+    HOTKEYS.with(|hotkeys| hotkeys.borrow().action(id))
+}
+
+// ----------------------------------------------------------------------------
+
+/// Registers hotkeys with the system and reports their presses to `HotKeyRegistry::action(..)`.
+pub(crate) trait HotKeyBackend {
+    /// `mask` holds the modifiers as in `Shortcut::mask()`.
+    fn register(&mut self, id: u32, key_code: KeyCode, mask: NSUInteger)
+        -> Result<(), HotKeyError>;
+    fn unregister(&mut self, id: u32);
+}
+
+/// The registered hotkeys and their actions, independent of how they're registered with the
+/// system.
+pub(crate) struct HotKeyRegistry<B: HotKeyBackend, A> {
+    backend: B,
+    layout: Layout,
+    hotkeys: Vec<(HotKeyId, Shortcut, A)>,
+    next_id: u32,
+}
+
+impl<B: HotKeyBackend, A: Clone> HotKeyRegistry<B, A> {
+    pub(crate) fn new(backend: B) -> Self {
+        Self {
+            backend,
+            layout: Layout::Us,
+            hotkeys: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    pub(crate) fn register(
+        &mut self,
+        shortcut: Shortcut,
+        action: A,
+    ) -> Result<HotKeyId, HotKeyError> {
+        if let Some((id, _, _)) = self.hotkeys.iter().find(|(_, other, _)| *other == shortcut) {
+            return Err(HotKeyError::Duplicate(*id));
+        }
+        if let Some(reserved_for) = reserved_for(&shortcut) {
+            return Err(HotKeyError::Reserved(reserved_for));
+        }
+
+        let (key, mask) = normalize(&shortcut.key.to_string(), shortcut.mask());
+        let key_code = key_code(&key, self.layout).ok_or(HotKeyError::UnknownKey)?;
+
+        let id = HotKeyId(self.next_id);
+        self.backend.register(id.0, key_code, mask)?;
+        self.next_id += 1;
+        self.hotkeys.push((id, shortcut, action));
+
+        Ok(id)
+    }
+
+    pub(crate) fn unregister(&mut self, id: HotKeyId) -> bool {
+        let Some(index) = self.hotkeys.iter().position(|(other, _, _)| *other == id) else {
+            return false;
+        };
+
+        self.backend.unregister(id.0);
+        self.hotkeys.remove(index);
+        true
+    }
+
+    pub(crate) fn registered(&self) -> Vec<(HotKeyId, Shortcut)> {
+        self.hotkeys
+            .iter()
+            .map(|(id, shortcut, _)| (*id, shortcut.clone()))
+            .collect()
+    }
+
+    /// The action of the hotkey the backend reported as pressed.
+    pub(crate) fn action(&self, id: u32) -> Option<A> {
+        self.hotkeys
+            .iter()
+            .find(|(other, _, _)| other.0 == id)
+            .map(|(_, _, action)| action.clone())
+    }
+}
+
+/// The physical key typing `key`, a normalized key equivalent.
+fn key_code(key: &str, layout: Layout) -> Option<KeyCode> {
+    let code = match key {
+        "\r" => KeyCode::RETURN,
+        "\t" => KeyCode::TAB,
+        " " => KeyCode::SPACE,
+        "\x7F" => KeyCode::DELETE,
+        "\u{1b}" => KeyCode::ESCAPE,
+        "\u{F704}" => KeyCode::F1,
+        "\u{F705}" => KeyCode::F2,
+        "\u{F706}" => KeyCode::F3,
+        "\u{F707}" => KeyCode::F4,
+        "\u{F708}" => KeyCode::F5,
+        "\u{F709}" => KeyCode::F6,
+        "\u{F70A}" => KeyCode::F7,
+        "\u{F70B}" => KeyCode::F8,
+        "\u{F70C}" => KeyCode::F9,
+        "\u{F70D}" => KeyCode::F10,
+        "\u{F70E}" => KeyCode::F11,
+        "\u{F70F}" => KeyCode::F12,
+        key => {
+            let mut chars = key.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) => layout.key_code(c)?,
+                _ => return None,
+            }
+        }
+    };
+
+    Some(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    const OPTION: NSUInteger = 1 << 19;
+    const COMMAND: NSUInteger = 1 << 20;
+
+    /// Records registrations, fails for key codes that "another app" registered.
+    #[derive(Default)]
+    struct FakeHotKeys {
+        registered: Vec<(u32, KeyCode, NSUInteger)>,
+        in_use: Vec<KeyCode>,
+    }
+
+    impl HotKeyBackend for FakeHotKeys {
+        fn register(
+            &mut self,
+            id: u32,
+            key_code: KeyCode,
+            mask: NSUInteger,
+        ) -> Result<(), HotKeyError> {
+            if self.in_use.contains(&key_code) {
+                return Err(HotKeyError::InUse);
+            }
+            self.registered.push((id, key_code, mask));
+            Ok(())
+        }
+
+        fn unregister(&mut self, id: u32) {
+            self.registered.retain(|(other, _, _)| *other != id);
+        }
+    }
+
+    fn registry() -> HotKeyRegistry<FakeHotKeys, &'static str> {
+        HotKeyRegistry::new(FakeHotKeys::default())
+    }
+
+    fn shortcut(key: Key, mask: NSUInteger) -> Shortcut {
+        Shortcut::new(key)
+            .option(mask & OPTION != 0)
+            .command(mask & COMMAND != 0)
+    }
+
+    #[test]
+    fn register_passes_physical_key_to_backend() {
+        let mut hotkeys = registry();
+        let space = Key::Other(String::from(" "));
+
+        let launcher = hotkeys.register(shortcut(space, OPTION), "launcher");
+        let find = hotkeys.register(shortcut(Key::F, OPTION | COMMAND), "find");
+
+        let (launcher, find) = (launcher.unwrap(), find.unwrap());
+        assert_ne!(launcher, find);
+        assert_eq!(
+            hotkeys.backend.registered,
+            [
+                (launcher.0, KeyCode::SPACE, OPTION),
+                (find.0, KeyCode::F, OPTION | COMMAND)
+            ]
+        );
+        assert_eq!(
+            hotkeys.registered(),
+            [
+                (
+                    launcher,
+                    Shortcut::new(Key::Other(String::from(" "))).option(true)
+                ),
+                (find, shortcut(Key::F, OPTION | COMMAND))
+            ]
+        );
+    }
+
+    #[test]
+    fn register_uses_layout() {
+        let mut hotkeys = registry();
+        hotkeys.layout = Layout::German;
+
+        hotkeys.register(shortcut(Key::Y, COMMAND), "undo").unwrap();
+        assert_eq!(hotkeys.backend.registered[0].1, KeyCode::Z);
+    }
+
+    #[test]
+    fn register_rejects_duplicates() {
+        let mut hotkeys = registry();
+        let id = hotkeys.register(shortcut(Key::E, OPTION), "a").unwrap();
+
+        assert_eq!(
+            hotkeys.register(shortcut(Key::E, OPTION), "b"),
+            Err(HotKeyError::Duplicate(id))
+        );
+        assert_eq!(hotkeys.backend.registered.len(), 1);
+        assert_eq!(hotkeys.action(id.0), Some("a"));
+    }
+
+    #[test]
+    fn register_rejects_reserved_and_unknown_keys() {
+        let mut hotkeys = registry();
+
+        assert_eq!(
+            hotkeys.register(shortcut(Key::Other(String::from(" ")), COMMAND), "a"),
+            Err(HotKeyError::Reserved("Spotlight"))
+        );
+        assert_eq!(
+            hotkeys.register(shortcut(Key::Other(String::from("€")), COMMAND), "a"),
+            Err(HotKeyError::UnknownKey)
+        );
+        assert!(hotkeys.backend.registered.is_empty());
+        assert!(hotkeys.registered().is_empty());
+    }
+
+    #[test]
+    fn register_reports_backend_errors() {
+        let mut hotkeys = registry();
+        hotkeys.backend.in_use.push(KeyCode::E);
+
+        assert_eq!(
+            hotkeys.register(shortcut(Key::E, OPTION), "a"),
+            Err(HotKeyError::InUse)
+        );
+        assert!(hotkeys.registered().is_empty());
+
+        let id = hotkeys.register(shortcut(Key::R, OPTION), "b").unwrap();
+        assert_eq!(hotkeys.action(id.0), Some("b"));
+    }
+
+    #[test]
+    fn unregister_removes_hotkey() {
+        let mut hotkeys = registry();
+        let a = hotkeys.register(shortcut(Key::A, OPTION), "a").unwrap();
+        let b = hotkeys.register(shortcut(Key::B, OPTION), "b").unwrap();
+
+        assert!(hotkeys.unregister(a));
+        assert!(!hotkeys.unregister(a));
+        assert_eq!(hotkeys.backend.registered, [(b.0, KeyCode::B, OPTION)]);
+        assert_eq!(hotkeys.registered(), [(b, shortcut(Key::B, OPTION))]);
+
+        // The shortcut can be registered again, with a new id
+        let again = hotkeys.register(shortcut(Key::A, OPTION), "c").unwrap();
+        assert_ne!(again, a);
+    }
+
+    #[test]
+    fn action_dispatches_by_backend_id() {
+        let mut hotkeys = registry();
+        let a = hotkeys.register(shortcut(Key::A, OPTION), "a").unwrap();
+        let b = hotkeys.register(shortcut(Key::B, OPTION), "b").unwrap();
+
+        assert_eq!(hotkeys.action(a.0), Some("a"));
+        assert_eq!(hotkeys.action(b.0), Some("b"));
+
+        hotkeys.unregister(a);
+        assert_eq!(hotkeys.action(a.0), None);
+        assert_eq!(hotkeys.action(0), None);
+    }
+}
@@ -1,21 +1,28 @@
 use std::{ptr::NonNull, sync::Arc};
 
 use icrate::{
-    AppKit::NSImage,
-    Foundation::{NSData, NSString},
+    block2::ConcreteBlock,
+    AppKit::{NSCompositingOperationSourceOver, NSImage},
+    Foundation::{CGFloat, NSData, NSObject, NSRect, NSString},
 };
 use libc::c_void;
-use objc2::{rc::Id, ClassType};
+use objc2::{class, msg_send, msg_send_id, rc::Id, runtime::Bool, ClassType};
+
+use crate::direction;
 
 // ----------------------------------------------------------------------------
 
+/// New variants may be added, so matches outside this crate need a wildcard arm.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Image {
     Static(&'static [u8]),
     Objc(Id<NSImage>),
     CopyOnPass(Arc<[u8]>),
     Named(String),
     SystemSymbol(String),
+    /// Mirrored horizontally in menus laid out right to left, see `Self::mirror_in_rtl(..)`.
+    MirrorInRtl(Box<Image>),
 }
 
 impl Image {
@@ -60,6 +67,17 @@ impl Image {
         Self::CopyOnPass(bytes.into())
     }
 
+    /// Mirrors the image horizontally in menus laid out right to left, e.g. for arrows and other
+    /// images that point in the reading direction, see `Menu::layout_direction(..)`.
+    pub fn mirror_in_rtl(self, mirror: bool) -> Self {
+        match (self, mirror) {
+            (Image::MirrorInRtl(image), false) => *image,
+            (image @ Image::MirrorInRtl(_), true) => image,
+            (image, true) => Image::MirrorInRtl(Box::new(image)),
+            (image, false) => image,
+        }
+    }
+
     pub(crate) fn to_objc(&self) -> Id<NSImage> {
         match self {
             Image::Static(bytes) => unsafe {
@@ -88,6 +106,35 @@ impl Image {
                 NSImage::imageWithSystemSymbolName_accessibilityDescription(&name, None)
                     .expect(&format!("System symbol '{}' does not exist", name))
             },
+            Image::MirrorInRtl(image) if direction::is_rtl() => mirrored(&image.to_objc()),
+            Image::MirrorInRtl(image) => image.to_objc(),
         }
     }
 }
+
+/// A copy of `image` flipped horizontally.
+fn mirrored(image: &NSImage) -> Id<NSImage> {
+    let source = image.retain();
+    let draw = ConcreteBlock::new(move |rect: NSRect| unsafe {
+        let transform: Id<NSObject> = msg_send_id![class!(NSAffineTransform), transform];
+        let _: () = msg_send![&*transform, translateXBy: rect.size.width yBy: 0.0 as CGFloat];
+        let _: () = msg_send![&*transform, scaleXBy: -1.0 as CGFloat yBy: 1.0 as CGFloat];
+        let _: () = msg_send![&*transform, concat];
+        source.drawInRect_fromRect_operation_fraction(
+            rect,
+            NSRect::ZERO,
+            NSCompositingOperationSourceOver,
+            1.0,
+        );
+        Bool::YES
+    })
+    .copy();
+
+    unsafe {
+        // Drawn on demand, so the mirrored image stays sharp on every screen
+        let mirrored = NSImage::imageWithSize_flipped_drawingHandler(image.size(), false, &draw);
+        // Symbols and other template images keep adapting to the menu's appearance
+        mirrored.setTemplate(image.isTemplate());
+        mirrored
+    }
+}
@@ -1,25 +1,54 @@
-use std::sync::Arc;
-
-use icrate::{AppKit::NSMenuItem, Foundation::NSString};
+use std::{any::Any, sync::Arc};
+
+use icrate::{
+    AppKit::{
+        NSApplication, NSControlStateValue, NSControlStateValueMixed, NSControlStateValueOff,
+        NSControlStateValueOn, NSMenu, NSMenuItem,
+    },
+    Foundation::{NSInteger, NSString},
+};
 use objc2::{
     class,
     declare::ClassBuilder,
-    msg_send,
+    ffi, msg_send,
     rc::Id,
-    runtime::{AnyClass, NSObject, Sel},
-    sel, ClassType,
+    runtime::{AnyClass, Bool, NSObject, Sel},
+    sel,
 };
 use once_cell::sync::Lazy;
 
-use crate::{id, nil, Image, Menu, Shortcut};
+use crate::{
+    alternate::KeyEquivalent,
+    capabilities::{Capabilities, SectionHeaderStyle},
+    chord::ChordMatcher,
+    conflicts::ShortcutEntry,
+    id,
+    keymap::{item_path, Keymap},
+    lazy::{LazyPolicy, LazyProvider, LazyState},
+    localize::{associate, LocalizedTitle, RealizedTitle, TitleDecorations},
+    nil, Badge, ChordShortcut, Image, ItemView, Menu, Modifiers, RichText, Shortcut,
+};
 
 // ----------------------------------------------------------------------------
 #[derive(Clone)]
 pub struct MenuItem {
     item_type: MenuItemType,
+    id: Option<String>,
+    tag: Option<isize>,
+    represented: Option<Represented>,
 
     enabled: bool,
     hidden: bool,
+    state: MenuItemState,
+    validate: Option<Validator>,
+    alternates: Vec<(Modifiers, MenuItem)>,
+
+    tooltip: Option<String>,
+    indentation_level: usize,
+    attributed_title: Option<RichText>,
+    localized_title: Option<LocalizedTitle>,
+    badge: Option<Badge>,
+    chord: Option<ChordShortcut>,
 
     image: Option<Image>,
     image_on: Option<Image>,
@@ -29,34 +58,30 @@ pub struct MenuItem {
 
 impl Default for MenuItem {
     fn default() -> Self {
-        Self {
-            item_type: MenuItemType::Dummy("default".into()),
-            enabled: true,
-            hidden: false,
-            image: None,
-            image_on: None,
-            image_off: None,
-            image_mixed: None,
-        }
+        Self::new(MenuItemType::Dummy("default".into()))
     }
 }
 
 impl MenuItem {
-    pub const SEPARATOR: Self = MenuItem {
-        item_type: MenuItemType::Separator,
-        enabled: true,
-        hidden: false,
-        image: None,
-        image_on: None,
-        image_off: None,
-        image_mixed: None,
-    };
+    pub const SEPARATOR: Self = Self::new(MenuItemType::Separator);
 
-    pub const fn dummy(title: String) -> Self {
+    const fn new(item_type: MenuItemType) -> Self {
         Self {
-            item_type: MenuItemType::Dummy(title),
+            item_type,
+            id: None,
+            tag: None,
+            represented: None,
             enabled: true,
             hidden: false,
+            state: MenuItemState::Off,
+            validate: None,
+            alternates: Vec::new(),
+            tooltip: None,
+            indentation_level: 0,
+            attributed_title: None,
+            localized_title: None,
+            badge: None,
+            chord: None,
             image: None,
             image_on: None,
             image_off: None,
@@ -64,29 +89,93 @@ impl MenuItem {
         }
     }
 
+    pub const fn dummy(title: String) -> Self {
+        Self::new(MenuItemType::Dummy(title))
+    }
+
+    /// A title for the items below it.
+    ///
+    /// # Info
+    /// Section headers require macOS 14, on older systems a disabled item with the title is shown.
+    pub const fn section_header(title: String) -> Self {
+        Self::new(MenuItemType::SectionHeader(title))
+    }
+
+    /// An item showing a custom view, e.g. a slider or a progress bar. The view is owned by the
+    /// realized item. See `ItemView` for how events are handled while the menu is open.
+    pub const fn custom_view(view: ItemView) -> Self {
+        Self::new(MenuItemType::CustomView(view))
+    }
+
     pub const fn sub_menu(menu: Menu) -> Self {
+        Self::new(MenuItemType::SubMenu(menu))
+    }
+
+    pub const fn button(title: String, action: Action, shortcut: Option<Shortcut>) -> Self {
+        Self::new(MenuItemType::Button(title, action, shortcut))
+    }
+
+    /// A submenu whose items are only built when it's about to open.
+    ///
+    /// Useful for submenus that are expensive to compute or contain many items.
+    pub fn lazy_sub_menu<F>(title: String, policy: LazyPolicy, provider: F) -> Self
+    where
+        F: Fn() -> Vec<MenuItem> + Send + Sync + 'static,
+    {
+        Self::new(MenuItemType::LazySubMenu(title, policy, Arc::new(provider)))
+    }
+
+    /// Identifies this item, e.g. in the result of `Menu::popup_select(..)`.
+    pub fn id(self, id: &str) -> Self {
         Self {
-            item_type: MenuItemType::SubMenu(menu),
-            enabled: true,
-            hidden: false,
-            image: None,
-            image_on: None,
-            image_off: None,
-            image_mixed: None,
+            id: Some(id.to_string()),
+            ..self
         }
     }
 
-    pub const fn button(title: String, action: Action, shortcut: Option<Shortcut>) -> Self {
+    pub fn get_id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn tag(self, tag: isize) -> Self {
         Self {
-            item_type: MenuItemType::Button(title, action, shortcut),
-            enabled: true,
-            hidden: false,
-            image: None,
-            image_on: None,
-            image_off: None,
-            image_mixed: None,
+            tag: Some(tag),
+            ..self
         }
     }
+
+    /// `None` if no tag was set, so a tag of 0 can be told apart from an untagged item.
+    pub fn get_tag(&self) -> Option<isize> {
+        self.tag
+    }
+
+    /// Attaches domain data to this item, e.g. a file path or a window id.
+    ///
+    /// It's passed to `Action::callback_with_context(..)` callbacks and can be looked up with
+    /// `Menu::item_with_represented(..)`.
+    pub fn represented<T>(self, represented: T) -> Self
+    where
+        T: Any + Send + Sync,
+    {
+        Self {
+            represented: Some(Arc::new(represented)),
+            ..self
+        }
+    }
+
+    /// The represented value, `None` if there's none or it's not a `T`.
+    pub fn get_represented<T: Any>(&self) -> Option<&T> {
+        self.represented.as_ref()?.downcast_ref()
+    }
+
+    pub(crate) fn context(&self) -> ItemContext {
+        ItemContext {
+            id: self.id.clone(),
+            tag: self.tag,
+            represented: self.represented.clone(),
+        }
+    }
+
     pub fn enabled(self, enabled: bool) -> Self {
         Self { enabled, ..self }
     }
@@ -95,6 +184,84 @@ impl MenuItem {
         Self { hidden, ..self }
     }
 
+    /// The initial state, replaced by the validator's state if there's one.
+    pub fn state(self, state: MenuItemState) -> Self {
+        Self { state, ..self }
+    }
+
+    /// Called every time the menu containing this item is about to open, to update the item's
+    /// enabled state, state and title.
+    pub fn validate<F>(self, validate: F) -> Self
+    where
+        F: Fn() -> Validation + Send + Sync + 'static,
+    {
+        Self {
+            validate: Some(Arc::new(validate)),
+            ..self
+        }
+    }
+
+    /// Whether this item or one of its alternates has a validator.
+    pub(crate) fn has_validator(&self) -> bool {
+        self.validate.is_some()
+            || self
+                .alternates
+                .iter()
+                .any(|(_, alternate)| alternate.has_validator())
+    }
+
+    /// Adds an item that replaces this one while `modifiers` are held, e.g. "Close All" for
+    /// "Close" while holding option.
+    ///
+    /// The alternate gets this item's shortcut with `modifiers` added, e.g. ⌥⌘W for an option
+    /// alternate of ⌘W. An alternate with its own shortcut gets `modifiers` added to that
+    /// shortcut instead, which must use the same key, see `Menu::check_alternates()`.
+    pub fn alternate(mut self, modifiers: Modifiers, item: MenuItem) -> Self {
+        self.alternates.push((modifiers, item));
+        self
+    }
+
+    pub fn tooltip(self, tooltip: Option<String>) -> Self {
+        Self { tooltip, ..self }
+    }
+
+    /// Indents the item, from 0 to 15.
+    pub fn indentation_level(self, indentation_level: usize) -> Self {
+        Self {
+            indentation_level: indentation_level.min(15),
+            ..self
+        }
+    }
+
+    /// Styled title shown instead of the plain title.
+    pub fn attributed_title(self, attributed_title: Option<RichText>) -> Self {
+        Self {
+            attributed_title,
+            ..self
+        }
+    }
+
+    /// Title translated by the current `Localizer` when the item is realized, shown instead of
+    /// the plain title. For submenus it's the title of the submenu as well.
+    pub fn localized_title(self, localized_title: Option<LocalizedTitle>) -> Self {
+        Self {
+            localized_title,
+            ..self
+        }
+    }
+
+    /// # Info
+    /// Badges require macOS 14, on older systems the badge text is appended to the title.
+    pub fn badge(self, badge: Option<Badge>) -> Self {
+        Self { badge, ..self }
+    }
+
+    /// Shows `chord` after the title, AppKit can't show it as a key equivalent. Chords are
+    /// dispatched by a `ChordMatcher`, see `ChordMatcher::from_menubar(..)`.
+    pub fn chord(self, chord: Option<ChordShortcut>) -> Self {
+        Self { chord, ..self }
+    }
+
     pub fn image(self, image: Option<Image>) -> Self {
         Self { image, ..self }
     }
@@ -115,15 +282,68 @@ impl MenuItem {
     }
 
     pub(crate) fn to_objc(&self) -> Id<NSMenuItem> {
-        let item = self.item_type.to_objc();
+        let item = match self.item_type {
+            MenuItemType::Button(ref title, Action::ContextCallback(ref action), ref shortcut) => {
+                let action = action.clone();
+                let context = self.context();
+                let action = Action::callback(move || action(&context));
+                MenuItemType::Button(title.clone(), action, shortcut.clone()).to_objc()
+            }
+            ref item_type => item_type.to_objc(),
+        };
 
         unsafe {
+            if let Some(tag) = self.tag {
+                item.setTag(tag as NSInteger);
+            }
+            if let Some(ref id) = self.id {
+                let id = NSString::from_str(id);
+                let _: () = msg_send![&*item, setIdentifier: &*id];
+            }
             if !self.enabled {
                 item.setEnabled(false);
             }
             if self.hidden {
                 item.setHidden(true);
             }
+            if self.state != MenuItemState::Off {
+                item.setState(self.state.to_objc());
+            }
+            if let Some(ref tooltip) = self.tooltip {
+                item.setToolTip(Some(&NSString::from_str(tooltip)));
+            }
+            if self.indentation_level > 0 {
+                item.setIndentationLevel(self.indentation_level as NSInteger);
+            }
+            let native_badges = Capabilities::current().badges;
+            if let Some(ref badge) = self.badge {
+                if native_badges {
+                    badge.apply(&item);
+                }
+            }
+            let decorations = TitleDecorations {
+                badge: self
+                    .badge
+                    .as_ref()
+                    .filter(|_| !native_badges)
+                    .map(Badge::text),
+                attributed_title: self.attributed_title.clone(),
+                chord: self.chord.as_ref().map(ChordShortcut::symbols),
+            };
+            match self.localized_title {
+                Some(ref localized_title) => {
+                    let title = RealizedTitle {
+                        title: localized_title.clone(),
+                        decorations,
+                    };
+                    title.apply(&item);
+                    associate(Id::as_ptr(&item) as *mut ffi::objc_object, title);
+                }
+                None if decorations != TitleDecorations::default() => {
+                    decorations.apply(&item, &item.title().to_string());
+                }
+                None => {}
+            }
             if let Some(ref img) = self.image {
                 item.setImage(Some(&img.to_objc()));
             }
@@ -136,10 +356,215 @@ impl MenuItem {
             if let Some(ref img_mixed) = self.image_mixed {
                 item.setMixedStateImage(Some(&img_mixed.to_objc()));
             }
+            if let Some(ref validate) = self.validate {
+                register_validator(Id::as_ptr(&item) as id, validate.clone());
+                validate_item(&item);
+            }
         }
 
         item
     }
+
+    /// Adds this item and its alternates to `menu`.
+    pub(crate) fn add_to(&self, menu: &NSMenu) {
+        unsafe {
+            menu.addItem(&self.to_objc());
+
+            let key_equivalents = self.key_equivalents().into_iter().skip(1);
+            for ((_, alternate), key_equivalent) in self.alternates.iter().zip(key_equivalents) {
+                let item = alternate.to_objc();
+                item.setKeyEquivalent(&NSString::from_str(&key_equivalent.key));
+                let _: () = msg_send![&*item, setKeyEquivalentModifierMask: key_equivalent.mask];
+                item.setAlternate(true);
+                menu.addItem(&item);
+            }
+        }
+    }
+
+    /// The key equivalents of this item and its alternates, as they are realized.
+    pub(crate) fn key_equivalents(&self) -> Vec<KeyEquivalent> {
+        let mask = self.shortcut().map_or(0, Shortcut::mask);
+        let mut key_equivalents = vec![KeyEquivalent {
+            key: self.key(),
+            mask,
+            alternate: false,
+        }];
+
+        for (modifiers, alternate) in self.alternates.iter() {
+            let (key, mask) = match alternate.shortcut() {
+                Some(shortcut) => (alternate.key(), shortcut.mask()),
+                None => (self.key(), mask),
+            };
+            key_equivalents.push(KeyEquivalent {
+                key,
+                mask: mask | modifiers.mask(),
+                alternate: true,
+            });
+        }
+
+        key_equivalents
+    }
+
+    /// The shortcuts of this item, its alternates and the items of its submenu, see
+    /// `MenuBar::shortcut_conflicts()`. Lazy submenus are skipped, their items aren't known yet.
+    pub(crate) fn shortcut_entries(&self, path: &[String], entries: &mut Vec<ShortcutEntry>) {
+        match self.item_type {
+            MenuItemType::Button(ref title, ref action, ref shortcut) => {
+                let item_path = |title: &str| {
+                    let mut path = path.to_vec();
+                    path.push(title.to_string());
+                    path
+                };
+
+                if let Some(shortcut) = shortcut {
+                    entries.push(ShortcutEntry {
+                        path: item_path(title),
+                        key: shortcut.key.to_string(),
+                        mask: shortcut.mask(),
+                        action: action.clone(),
+                    });
+                }
+
+                let key_equivalents = self.key_equivalents().into_iter().skip(1);
+                for ((_, alternate), key_equivalent) in self.alternates.iter().zip(key_equivalents)
+                {
+                    if let MenuItemType::Button(ref title, ref action, _) = alternate.item_type {
+                        if !key_equivalent.key.is_empty() {
+                            entries.push(ShortcutEntry {
+                                path: item_path(title),
+                                key: key_equivalent.key,
+                                mask: key_equivalent.mask,
+                                action: action.clone(),
+                            });
+                        }
+                    }
+                }
+            }
+            MenuItemType::SubMenu(ref menu) => menu.shortcut_entries(path, entries),
+            _ => {}
+        }
+    }
+
+    /// Binds this item and the items of its submenu to their shortcuts, see
+    /// `Keymap::from_menubar(..)`.
+    pub(crate) fn keymap_entries(&self, path: &[String], keymap: &mut Keymap) {
+        match self.item_type {
+            MenuItemType::Button(ref title, _, Some(ref shortcut)) => {
+                let target = match self.id {
+                    Some(ref id) => id.clone(),
+                    None => item_path(path, title),
+                };
+                keymap.bind(&target, Some(shortcut.clone()));
+            }
+            MenuItemType::SubMenu(ref menu) => menu.keymap_entries(path, keymap),
+            _ => {}
+        }
+    }
+
+    /// Replaces the shortcuts of this item and the items of its submenu that are bound in
+    /// `keymap`, collecting the matched targets.
+    pub(crate) fn apply_keymap(
+        &mut self,
+        path: &[String],
+        keymap: &Keymap,
+        matched: &mut Vec<String>,
+    ) {
+        match self.item_type {
+            MenuItemType::Button(ref title, _, ref mut shortcut) => {
+                let path = item_path(path, title);
+                if let Some(binding) = keymap.lookup(self.id.as_deref(), &path, matched) {
+                    *shortcut = binding;
+                }
+            }
+            MenuItemType::SubMenu(ref mut menu) => menu.apply_keymap(path, keymap, matched),
+            _ => {}
+        }
+    }
+
+    /// Binds the chords of this item and the items of its submenu to their actions.
+    pub(crate) fn chord_entries(&self, matcher: &mut ChordMatcher<Action>) {
+        match (&self.item_type, &self.chord) {
+            (MenuItemType::Button(..), Some(chord)) => {
+                matcher.bind(chord.clone(), self.chosen_action().unwrap());
+            }
+            (MenuItemType::SubMenu(menu), _) => menu.chord_entries(matcher),
+            _ => {}
+        }
+    }
+
+    /// The action performed when this item is chosen, context callbacks get this item's context.
+    pub(crate) fn chosen_action(&self) -> Option<Action> {
+        match self.item_type {
+            MenuItemType::Button(_, Action::ContextCallback(ref action), _) => {
+                let action = action.clone();
+                let context = self.context();
+                Some(Action::callback(move || action(&context)))
+            }
+            MenuItemType::Button(_, ref action, _) => Some(action.clone()),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn shortcut(&self) -> Option<&Shortcut> {
+        match self.item_type {
+            MenuItemType::Button(_, _, ref shortcut) => shortcut.as_ref(),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn submenu(&self) -> Option<&Menu> {
+        match self.item_type {
+            MenuItemType::SubMenu(ref menu) => Some(menu),
+            _ => None,
+        }
+    }
+
+    fn key(&self) -> String {
+        self.shortcut()
+            .map_or(String::new(), |shortcut| shortcut.key.to_string())
+    }
+
+    /// Copy of this item that calls `hook` with its id when chosen. With `recursive`, items in its
+    /// submenu and its alternates are hooked as well.
+    pub(crate) fn hook_chosen(&self, hook: &ChosenHook, recursive: bool) -> Self {
+        let item_type = match (&self.item_type, &self.id) {
+            (MenuItemType::Button(title, action, shortcut), Some(id)) => MenuItemType::Button(
+                title.clone(),
+                action.hook_chosen(id, hook),
+                shortcut.clone(),
+            ),
+            (MenuItemType::SubMenu(menu), _) if recursive => {
+                MenuItemType::SubMenu(menu.hook_chosen(hook))
+            }
+            (MenuItemType::LazySubMenu(title, policy, provider), _) if recursive => {
+                let provider = provider.clone();
+                let hook = hook.clone();
+                let provider: LazyProvider = Arc::new(move || {
+                    provider()
+                        .iter()
+                        .map(|item| item.hook_chosen(&hook, true))
+                        .collect()
+                });
+                MenuItemType::LazySubMenu(title.clone(), *policy, provider)
+            }
+            (item_type, _) => item_type.clone(),
+        };
+
+        let alternates = if recursive {
+            self.alternates
+                .iter()
+                .map(|(modifiers, item)| (*modifiers, item.hook_chosen(hook, true)))
+                .collect()
+        } else {
+            self.alternates.clone()
+        };
+
+        Self {
+            item_type,
+            alternates,
+            ..self.clone()
+        }
+    }
 }
 
 // ----------------------------------------------------------------------------
@@ -148,47 +573,75 @@ impl MenuItem {
 enum MenuItemType {
     Dummy(String),
 
+    SectionHeader(String),
+
     Button(String, Action, Option<Shortcut>),
 
+    CustomView(ItemView),
+
     SubMenu(Menu),
 
+    LazySubMenu(String, LazyPolicy, LazyProvider),
+
     Separator,
 }
 
 impl MenuItemType {
     fn to_objc(&self) -> Id<NSMenuItem> {
         match self {
-            MenuItemType::Dummy(title) => unsafe {
-                let title = NSString::from_str(title);
-                let key = NSString::from_str("");
-                let alloc = NSMenuItem::alloc();
-                NSMenuItem::initWithTitle_action_keyEquivalent(alloc, &title, None, &key)
+            MenuItemType::Dummy(title) => unsafe { new_cacao_item(title, None, "") },
+            MenuItemType::SectionHeader(title) => unsafe {
+                match Capabilities::current().section_header_style(title) {
+                    SectionHeaderStyle::Native => {
+                        let title = NSString::from_str(title);
+                        let item: id =
+                            msg_send![class!(NSMenuItem), sectionHeaderWithTitle: &*title];
+                        Id::retain(item as *mut NSMenuItem).unwrap()
+                    }
+                    SectionHeaderStyle::Fallback(header) => {
+                        let item = new_cacao_item(&header.title, None, "");
+                        item.setEnabled(header.enabled);
+                        item
+                    }
+                }
+            },
+            MenuItemType::CustomView(view) => unsafe {
+                let item = new_cacao_item("", None, "");
+                item.setView(Some(&view.to_objc()));
+                item
             },
             MenuItemType::SubMenu(menu) => unsafe {
-                let item = NSMenuItem::new();
+                let item = new_cacao_item("", None, "");
+                item.setSubmenu(Some(&menu.to_objc()));
+                item
+            },
+            MenuItemType::LazySubMenu(title, policy, provider) => unsafe {
+                let provider = provider.clone();
+                let state = LazyState::new(*policy);
+                let menu = Menu::new(title, Vec::new())
+                    .on_needs_update(move || state.begin_update().then(|| provider()));
+
+                let item = new_cacao_item("", None, "");
                 item.setSubmenu(Some(&menu.to_objc()));
                 item
             },
             MenuItemType::Separator => unsafe { NSMenuItem::separatorItem() },
             MenuItemType::Button(title, action, shortcut) => unsafe {
-                let alloc: id = msg_send![register_menu_item_class(), alloc];
-
-                let title = NSString::from_str(title);
                 let key = if let Some(shortcut) = shortcut {
-                    NSString::from_str(&shortcut.key.to_string())
+                    shortcut.key.to_string()
                 } else {
-                    NSString::from_str("")
+                    String::new()
                 };
-                let item: id = msg_send![alloc, initWithTitle:&*title action: action.to_sel() keyEquivalent:&*key];
+                let item = new_cacao_item(title, Some(action.to_sel()), &key);
                 if let Some(shortcut) = shortcut {
-                    let _: () = msg_send![item, setKeyEquivalentModifierMask: shortcut.mask()];
+                    let _: () = msg_send![&*item, setKeyEquivalentModifierMask: shortcut.mask()];
                 }
 
                 if let Action::Callback(action) = action {
-                    register_callback(item, action.clone());
+                    register_callback(Id::as_ptr(&item) as id, action.clone());
                 }
 
-                Id::new(item as *mut NSMenuItem).unwrap()
+                item
             },
         }
     }
@@ -196,7 +649,80 @@ impl MenuItemType {
 
 // ----------------------------------------------------------------------------
 
+/// The item's state, shown as a checkmark (`On`) or dash (`Mixed`) by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MenuItemState {
+    Off,
+    On,
+    Mixed,
+}
+
+impl MenuItemState {
+    fn to_objc(self) -> NSControlStateValue {
+        match self {
+            MenuItemState::Off => NSControlStateValueOff,
+            MenuItemState::On => NSControlStateValueOn,
+            MenuItemState::Mixed => NSControlStateValueMixed,
+        }
+    }
+}
+
+/// Result of `MenuItem::validate(..)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validation {
+    pub enabled: bool,
+    pub state: MenuItemState,
+    /// Replaces the item's title, `None` keeps the current title.
+    pub title: Option<String>,
+}
+
+impl Default for Validation {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            state: MenuItemState::Off,
+            title: None,
+        }
+    }
+}
+
+type Validator = Arc<dyn Fn() -> Validation + Send + Sync + 'static>;
+
+type Represented = Arc<dyn Any + Send + Sync + 'static>;
+
+/// The item whose action is performed, see `Action::callback_with_context(..)`.
+#[derive(Clone, Default)]
+pub struct ItemContext {
+    id: Option<String>,
+    tag: Option<isize>,
+    represented: Option<Represented>,
+}
+
+impl ItemContext {
+    pub fn id(&self) -> Option<&str> {
+        self.id.as_deref()
+    }
+
+    pub fn tag(&self) -> Option<isize> {
+        self.tag
+    }
+
+    /// The represented value of the item, `None` if there's none or it's not a `T`.
+    pub fn represented<T: Any>(&self) -> Option<&T> {
+        self.represented.as_ref()?.downcast_ref()
+    }
+}
+
+/// Called with the id of a chosen item, see `MenuItem::hook_chosen(..)`.
+pub(crate) type ChosenHook = Arc<dyn Fn(&str) + Send + Sync + 'static>;
+
+// ----------------------------------------------------------------------------
+
+/// What happens when an item is chosen.
+///
+/// New variants may be added, so matches outside this crate need a wildcard arm.
 #[derive(Clone)]
+#[non_exhaustive]
 pub enum Action {
     Hide,
     HideOthers,
@@ -207,6 +733,7 @@ pub enum Action {
     Minimize,
     None,
     Callback(Arc<dyn Fn() + Send + Sync + 'static>),
+    ContextCallback(Arc<dyn Fn(&ItemContext) + Send + Sync + 'static>),
 }
 
 impl Action {
@@ -217,7 +744,50 @@ impl Action {
         Self::Callback(Arc::new(f))
     }
 
-    fn to_sel(&self) -> Sel {
+    /// A callback that receives the id, tag and represented value of the chosen item.
+    pub fn callback_with_context<F>(f: F) -> Self
+    where
+        F: Fn(&ItemContext) + Send + Sync + 'static,
+    {
+        Self::ContextCallback(Arc::new(f))
+    }
+
+    /// Performs this action directly, as if its item was chosen.
+    pub(crate) fn perform(&self) {
+        match self {
+            Action::None => {}
+            Action::Callback(action) => action(),
+            Action::ContextCallback(action) => action(&ItemContext::default()),
+            action => unsafe {
+                let app = NSApplication::sharedApplication();
+                app.sendAction_to_from(action.to_sel(), None, None);
+            },
+        }
+    }
+
+    fn hook_chosen(&self, id: &str, hook: &ChosenHook) -> Self {
+        let id = id.to_string();
+        let hook = hook.clone();
+
+        match self {
+            Action::ContextCallback(action) => {
+                let action = action.clone();
+                Self::callback_with_context(move |context| {
+                    hook(&id);
+                    action(context);
+                })
+            }
+            action => {
+                let action = action.clone();
+                Self::callback(move || {
+                    hook(&id);
+                    action.perform();
+                })
+            }
+        }
+    }
+
+    pub(crate) fn to_sel(&self) -> Sel {
         match self {
             Action::Hide => sel!(hide:),
             Action::HideOthers => sel!(hideOtherApplications:),
@@ -227,6 +797,7 @@ impl Action {
             Action::ToggleFullScreen => sel!(toggleFullScreen:),
             Action::Minimize => sel!(performMiniaturize:),
             Action::Callback(_) => sel!(fireBlockAction:),
+            Action::ContextCallback(_) => sel!(fireBlockAction:),
             Action::None => sel!(fireBlockAction:),
         }
     }
@@ -234,7 +805,18 @@ impl Action {
 
 // ----------------------------------------------------------------------------
 
-static BLOCK_PTR: &'static str = "cacaoMenuItemBlockPtr";
+static BLOCK_PTR: &str = "cacaoMenuItemBlockPtr";
+static VALIDATE_PTR: &str = "cacaoMenuItemValidatePtr";
+
+unsafe fn new_cacao_item(title: &str, action: Option<Sel>, key: &str) -> Id<NSMenuItem> {
+    let alloc: id = msg_send![register_menu_item_class(), alloc];
+
+    let title = NSString::from_str(title);
+    let key = NSString::from_str(key);
+    let item: id = msg_send![alloc, initWithTitle:&*title action:action keyEquivalent:&*key];
+
+    Id::new(item as *mut NSMenuItem).unwrap()
+}
 
 fn register_callback(objc: id, action: Arc<dyn Fn() + 'static>) {
     let handler = Box::new(action);
@@ -246,6 +828,42 @@ fn register_callback(objc: id, action: Arc<dyn Fn() + 'static>) {
     }
 }
 
+fn register_validator(objc: id, validate: Validator) {
+    let validate = Box::new(validate);
+    let ptr = Box::into_raw(validate);
+
+    unsafe {
+        (&mut *objc).set_ivar(VALIDATE_PTR, ptr as usize);
+    }
+}
+
+/// Runs the validator of `item`, if any, and applies the result. Returns whether the item is
+/// enabled.
+pub(crate) fn validate_item(item: &NSMenuItem) -> bool {
+    unsafe {
+        let is_cacao: bool = msg_send![item, isKindOfClass: register_menu_item_class()];
+        if !is_cacao {
+            return item.isEnabled();
+        }
+
+        let ptr: usize = *item.ivar(VALIDATE_PTR);
+        if ptr == 0 {
+            return item.isEnabled();
+        }
+
+        let validate = &*(ptr as *const Validator);
+        let validation = validate();
+
+        item.setEnabled(validation.enabled);
+        item.setState(validation.state.to_objc());
+        if let Some(title) = validation.title {
+            item.setTitle(&NSString::from_str(&title));
+        }
+
+        validation.enabled
+    }
+}
+
 extern "C" fn dealloc_cacao_menuitem(this: &NSObject, _: Sel) {
     unsafe {
         let ptr: usize = *this.ivar(BLOCK_PTR);
@@ -255,6 +873,13 @@ extern "C" fn dealloc_cacao_menuitem(this: &NSObject, _: Sel) {
             let _handler = Box::from_raw(obj);
         }
 
+        let ptr: usize = *this.ivar(VALIDATE_PTR);
+        let obj = ptr as *mut Validator;
+
+        if !obj.is_null() {
+            let _validate = Box::from_raw(obj);
+        }
+
         let _: () = msg_send![this, setTarget:nil];
 
         let _: () = msg_send![super(this, class!(NSMenuItem)), dealloc];
@@ -266,11 +891,22 @@ extern "C" fn fire_block_action(this: &NSObject, _: Sel, _item: id) {
     action();
 }
 
+/// Only called when the menu auto-enables its items, see `Menu::autoenables_items(..)`.
+extern "C" fn validate_menu_item(this: &NSObject, _: Sel, item: &NSMenuItem) -> Bool {
+    let ptr: usize = unsafe { *this.ivar(VALIDATE_PTR) };
+    if ptr == 0 {
+        return Bool::YES;
+    }
+
+    Bool::new(validate_item(item))
+}
+
 pub(crate) fn register_menu_item_class() -> &'static AnyClass {
     static CLASS: Lazy<&'static AnyClass> = Lazy::new(|| unsafe {
         let mut builder = ClassBuilder::new("CacaoMenuItem", class!(NSMenuItem)).unwrap();
 
         builder.add_ivar::<usize>(BLOCK_PTR);
+        builder.add_ivar::<usize>(VALIDATE_PTR);
 
         builder.add_method(
             sel!(dealloc),
@@ -282,6 +918,11 @@ pub(crate) fn register_menu_item_class() -> &'static AnyClass {
             fire_block_action as unsafe extern "C" fn(_, _, id),
         );
 
+        builder.add_method(
+            sel!(validateMenuItem:),
+            validate_menu_item as unsafe extern "C" fn(_, _, _) -> _,
+        );
+
         builder.register()
     });
 
@@ -295,3 +936,83 @@ pub fn load<'a, T>(this: &'a NSObject, ptr_name: &str) -> &'a T {
         &*obj
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::conflicts::find_conflicts;
+    use crate::{Key, ShortcutConflict};
+
+    const SHIFT: usize = 1 << 17;
+    const OPTION: usize = 1 << 19;
+    const COMMAND: usize = 1 << 20;
+
+    #[test]
+    fn alternate_adds_modifiers_to_primary_shortcut() {
+        let close_all = MenuItem::button("Close All".to_string(), Action::None, None);
+        let close = MenuItem::button(
+            "Close".to_string(),
+            Action::CloseWindow,
+            Some(Shortcut::new(Key::W).command(true)),
+        )
+        .alternate(Modifiers::new().option(true), close_all);
+
+        let key_equivalents = close.key_equivalents();
+        assert_eq!(key_equivalents[1].key, "w");
+        assert_eq!(key_equivalents[1].mask, COMMAND | OPTION);
+    }
+
+    #[test]
+    fn alternate_keeps_own_shortcut() {
+        let close_all = MenuItem::button(
+            "Close All".to_string(),
+            Action::None,
+            Some(Shortcut::new(Key::W).command(true).shift(true)),
+        );
+        let close = MenuItem::button(
+            "Close".to_string(),
+            Action::CloseWindow,
+            Some(Shortcut::new(Key::W).command(true)),
+        )
+        .alternate(Modifiers::new().option(true), close_all);
+
+        let key_equivalents = close.key_equivalents();
+        assert_eq!(key_equivalents[1].key, "w");
+        assert_eq!(key_equivalents[1].mask, COMMAND | OPTION | SHIFT);
+    }
+
+    #[test]
+    fn conflicts_check_realized_alternate_shortcuts() {
+        let close_all = MenuItem::button("Close All".to_string(), Action::None, None);
+        let close = MenuItem::button(
+            "Close".to_string(),
+            Action::CloseWindow,
+            Some(Shortcut::new(Key::W).command(true)),
+        )
+        .alternate(Modifiers::new().option(true), close_all);
+        let other = MenuItem::button(
+            "Close Tabs".to_string(),
+            Action::None,
+            Some(Shortcut::new(Key::W).command(true).option(true)),
+        );
+        let wrap = MenuItem::button(
+            "Wrap".to_string(),
+            Action::None,
+            Some(Shortcut::new(Key::W).option(true)),
+        );
+
+        let mut entries = Vec::new();
+        for item in [&close, &other, &wrap] {
+            item.shortcut_entries(&["File".to_string()], &mut entries);
+        }
+
+        let path = |title: &str| vec!["File".to_string(), title.to_string()];
+        assert_eq!(
+            find_conflicts(&entries),
+            [ShortcutConflict::Duplicate {
+                shortcut: "⌥⌘W".to_string(),
+                paths: vec![path("Close All"), path("Close Tabs")],
+            }]
+        );
+    }
+}
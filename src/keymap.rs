@@ -0,0 +1,339 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+#[cfg(target_os = "macos")]
+use crate::MenuBar;
+use crate::{ParseShortcutError, Shortcut, ShortcutConflict};
+
+// ----------------------------------------------------------------------------
+
+/// Shortcut overrides for the items of a `MenuBar`, e.g. loaded from a user's config file.
+///
+/// Items are targeted by their id, see `MenuItem::id(..)`, or by the titles from the top-level
+/// menu down to the item joined by " > ", e.g. "File > Export". A target bound to `None` has its
+/// shortcut removed.
+///
+/// In files, targets map to shortcut strings as parsed by `Shortcut::from_str(..)`, an empty
+/// string removes the shortcut:
+/// ```toml
+/// "file.export" = "Cmd+Shift+E"
+/// "Edit > Find > Find Next" = ""
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    bindings: BTreeMap<String, Option<Shortcut>>,
+}
+
+/// The result of `Keymap::apply(..)`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct KeymapReport {
+    /// Targets that matched no item.
+    pub unknown: Vec<String>,
+    /// Conflicts in the menu bar after applying the keymap, see `MenuBar::shortcut_conflicts()`.
+    pub conflicts: Vec<ShortcutConflict>,
+}
+
+#[derive(Debug)]
+pub enum KeymapError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    Toml(toml::de::Error),
+    /// A target is bound to a string that isn't a shortcut.
+    InvalidShortcut(String, ParseShortcutError),
+    /// The file extension is neither `json` nor `toml`.
+    UnknownFormat,
+}
+
+impl Keymap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Binds `target` to `shortcut`, `None` removes the item's shortcut.
+    pub fn bind(&mut self, target: &str, shortcut: Option<Shortcut>) {
+        self.bindings.insert(target.to_string(), shortcut);
+    }
+
+    pub fn unbind(&mut self, target: &str) {
+        self.bindings.remove(target);
+    }
+
+    /// The binding of `target`, `None` if it isn't bound.
+    pub fn get(&self, target: &str) -> Option<Option<&Shortcut>> {
+        self.bindings.get(target).map(Option::as_ref)
+    }
+
+    /// The bound targets, sorted.
+    pub fn targets(&self) -> impl Iterator<Item = &str> {
+        self.bindings.keys().map(String::as_str)
+    }
+
+    /// Adds the bindings of `other`, replacing existing bindings of the same targets.
+    pub fn merge(&mut self, other: Keymap) {
+        self.bindings.extend(other.bindings);
+    }
+
+    /// The binding for an item with `id` and `path`, the id taking precedence.
+    ///
+    /// Both targets are added to `matched` if both are bound, the path's binding is ignored but
+    /// it still names an existing item.
+    pub(crate) fn lookup(
+        &self,
+        id: Option<&str>,
+        path: &str,
+        matched: &mut Vec<String>,
+    ) -> Option<Option<Shortcut>> {
+        let mut binding = None;
+        for target in id.into_iter().chain([path]) {
+            if let Some(shortcut) = self.bindings.get(target) {
+                binding.get_or_insert_with(|| shortcut.clone());
+                matched.push(target.to_string());
+            }
+        }
+
+        binding
+    }
+
+    /// Loads a keymap from a `.json` or `.toml` file.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeymapError> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(KeymapError::Io)?;
+
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => Self::from_json(&text),
+            Some("toml") => Self::from_toml(&text),
+            _ => Err(KeymapError::UnknownFormat),
+        }
+    }
+
+    /// Saves the keymap to a `.json` or `.toml` file.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), KeymapError> {
+        let path = path.as_ref();
+        let text = match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => self.to_json(),
+            Some("toml") => self.to_toml(),
+            _ => return Err(KeymapError::UnknownFormat),
+        };
+
+        fs::write(path, text).map_err(KeymapError::Io)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, KeymapError> {
+        Self::from_strings(serde_json::from_str(json).map_err(KeymapError::Json)?)
+    }
+
+    pub fn from_toml(toml: &str) -> Result<Self, KeymapError> {
+        Self::from_strings(toml::from_str(toml).map_err(KeymapError::Toml)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(&self.to_strings()).unwrap()
+    }
+
+    pub fn to_toml(&self) -> String {
+        toml::to_string(&self.to_strings()).unwrap()
+    }
+
+    fn from_strings(strings: BTreeMap<String, String>) -> Result<Self, KeymapError> {
+        let mut keymap = Self::new();
+        for (target, shortcut) in strings {
+            let shortcut = match shortcut.trim() {
+                "" => None,
+                shortcut => Some(
+                    shortcut
+                        .parse()
+                        .map_err(|err| KeymapError::InvalidShortcut(target.clone(), err))?,
+                ),
+            };
+            keymap.bind(&target, shortcut);
+        }
+
+        Ok(keymap)
+    }
+
+    fn to_strings(&self) -> BTreeMap<String, String> {
+        self.bindings
+            .iter()
+            .map(|(target, shortcut)| {
+                let shortcut = shortcut.as_ref().map_or(String::new(), Shortcut::to_string);
+                (target.clone(), shortcut)
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Keymap {
+    /// The shortcuts of all items of `menubar` that have one, targeted by id if they have one.
+    ///
+    /// Lazy submenus and alternates aren't included.
+    pub fn from_menubar(menubar: &MenuBar) -> Self {
+        let mut keymap = Self::new();
+        for menu in menubar.main_menu.iter().chain(menubar.menus.iter()) {
+            menu.keymap_entries(&[], &mut keymap);
+        }
+
+        keymap
+    }
+
+    /// Replaces the shortcuts of the targeted items of `menubar`.
+    pub fn apply(&self, menubar: &mut MenuBar) -> KeymapReport {
+        let mut matched = Vec::new();
+        for menu in menubar.main_menu.iter_mut().chain(menubar.menus.iter_mut()) {
+            menu.apply_keymap(&[], self, &mut matched);
+        }
+
+        KeymapReport {
+            unknown: self
+                .targets()
+                .filter(|target| !matched.iter().any(|matched| matched == target))
+                .map(String::from)
+                .collect(),
+            conflicts: menubar.shortcut_conflicts(),
+        }
+    }
+}
+
+impl fmt::Display for KeymapError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeymapError::Io(err) => write!(f, "{}", err),
+            KeymapError::Json(err) => write!(f, "{}", err),
+            KeymapError::Toml(err) => write!(f, "{}", err),
+            KeymapError::InvalidShortcut(target, err) => write!(f, "{}: {}", target, err),
+            KeymapError::UnknownFormat => write!(f, "keymap files must be .json or .toml"),
+        }
+    }
+}
+
+impl Error for KeymapError {}
+
+/// The path of an item as used by `Keymap`, e.g. "File > Export".
+pub(crate) fn item_path(path: &[String], title: &str) -> String {
+    let mut path = path.join(" > ");
+    if !path.is_empty() {
+        path.push_str(" > ");
+    }
+    path.push_str(title);
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Key;
+
+    fn keymap() -> Keymap {
+        let mut keymap = Keymap::new();
+        keymap.bind(
+            "file.export",
+            Some(Shortcut::new(Key::E).command(true).shift(true)),
+        );
+        keymap.bind("Edit > Find > Find Next", None);
+        keymap
+    }
+
+    fn bindings(keymap: &Keymap) -> Vec<(String, Option<Shortcut>)> {
+        keymap
+            .targets()
+            .map(|target| (target.to_string(), keymap.get(target).unwrap().cloned()))
+            .collect()
+    }
+
+    #[test]
+    fn json_and_toml_round_trip() {
+        let keymap = keymap();
+
+        let json = Keymap::from_json(&keymap.to_json()).unwrap();
+        assert_eq!(bindings(&json), bindings(&keymap));
+
+        let toml = Keymap::from_toml(&keymap.to_toml()).unwrap();
+        assert_eq!(bindings(&toml), bindings(&keymap));
+    }
+
+    #[test]
+    fn files_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let keymap = keymap();
+
+        for name in ["keymap.json", "keymap.toml"] {
+            let path = dir.path().join(name);
+            keymap.save(&path).unwrap();
+            assert_eq!(bindings(&Keymap::load(&path).unwrap()), bindings(&keymap));
+        }
+    }
+
+    #[test]
+    fn empty_string_removes_shortcut() {
+        let keymap = Keymap::from_toml(r#""file.close" = """#).unwrap();
+        assert_eq!(keymap.get("file.close"), Some(None));
+
+        let keymap = Keymap::from_json(r#"{ "file.close": " " }"#).unwrap();
+        assert_eq!(keymap.get("file.close"), Some(None));
+        assert_eq!(keymap.to_json(), "{\n  \"file.close\": \"\"\n}");
+    }
+
+    #[test]
+    fn invalid_shortcut_names_target() {
+        let err = Keymap::from_json(r#"{ "file.export": "Cmd+Nope" }"#).unwrap_err();
+        assert!(
+            matches!(err, KeymapError::InvalidShortcut(ref target, _) if target == "file.export")
+        );
+    }
+
+    #[test]
+    fn unknown_extension_is_rejected() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keymap.txt");
+
+        assert!(matches!(
+            keymap().save(&path),
+            Err(KeymapError::UnknownFormat)
+        ));
+        assert!(!path.exists());
+
+        fs::write(&path, "").unwrap();
+        assert!(matches!(
+            Keymap::load(&path),
+            Err(KeymapError::UnknownFormat)
+        ));
+    }
+
+    #[test]
+    fn lookup_prefers_id_and_matches_both_targets() {
+        let mut keymap = Keymap::new();
+        keymap.bind("file.export", Some(Shortcut::new(Key::E).command(true)));
+        keymap.bind("File > Export", None);
+
+        let mut matched = Vec::new();
+        assert_eq!(
+            keymap.lookup(Some("file.export"), "File > Export", &mut matched),
+            Some(Some(Shortcut::new(Key::E).command(true)))
+        );
+        assert_eq!(matched, ["file.export", "File > Export"]);
+
+        let mut matched = Vec::new();
+        assert_eq!(
+            keymap.lookup(Some("file.import"), "File > Export", &mut matched),
+            Some(None)
+        );
+        assert_eq!(matched, ["File > Export"]);
+
+        let mut matched = Vec::new();
+        assert_eq!(keymap.lookup(None, "File > Import", &mut matched), None);
+        assert!(matched.is_empty());
+    }
+
+    #[test]
+    fn item_paths() {
+        assert_eq!(item_path(&[], "Quit"), "Quit");
+        assert_eq!(
+            item_path(&["Edit".to_string(), "Find".to_string()], "Find Next"),
+            "Edit > Find > Find Next"
+        );
+    }
+}
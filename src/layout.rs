@@ -0,0 +1,231 @@
+// ----------------------------------------------------------------------------
+
+/// A physical key, as an ANSI virtual key code (`kVK_ANSI_*` in Carbon's `Events.h`).
+///
+/// The codes name the key at that position on a US keyboard, e.g. `KeyCode::Z` is the key left
+/// of X, which types "y" on a German layout. See `Shortcut::physical(..)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyCode(pub u16);
+
+impl KeyCode {
+    pub const A: Self = Self(0x00);
+    pub const S: Self = Self(0x01);
+    pub const D: Self = Self(0x02);
+    pub const F: Self = Self(0x03);
+    pub const H: Self = Self(0x04);
+    pub const G: Self = Self(0x05);
+    pub const Z: Self = Self(0x06);
+    pub const X: Self = Self(0x07);
+    pub const C: Self = Self(0x08);
+    pub const V: Self = Self(0x09);
+    /// The key left of 1 on ISO keyboards.
+    pub const SECTION: Self = Self(0x0A);
+    pub const B: Self = Self(0x0B);
+    pub const Q: Self = Self(0x0C);
+    pub const W: Self = Self(0x0D);
+    pub const E: Self = Self(0x0E);
+    pub const R: Self = Self(0x0F);
+    pub const Y: Self = Self(0x10);
+    pub const T: Self = Self(0x11);
+    pub const NUM_1: Self = Self(0x12);
+    pub const NUM_2: Self = Self(0x13);
+    pub const NUM_3: Self = Self(0x14);
+    pub const NUM_4: Self = Self(0x15);
+    pub const NUM_6: Self = Self(0x16);
+    pub const NUM_5: Self = Self(0x17);
+    pub const EQUAL: Self = Self(0x18);
+    pub const NUM_9: Self = Self(0x19);
+    pub const NUM_7: Self = Self(0x1A);
+    pub const MINUS: Self = Self(0x1B);
+    pub const NUM_8: Self = Self(0x1C);
+    pub const NUM_0: Self = Self(0x1D);
+    pub const RIGHT_BRACKET: Self = Self(0x1E);
+    pub const O: Self = Self(0x1F);
+    pub const U: Self = Self(0x20);
+    pub const LEFT_BRACKET: Self = Self(0x21);
+    pub const I: Self = Self(0x22);
+    pub const P: Self = Self(0x23);
+    pub const RETURN: Self = Self(0x24);
+    pub const L: Self = Self(0x25);
+    pub const J: Self = Self(0x26);
+    pub const QUOTE: Self = Self(0x27);
+    pub const K: Self = Self(0x28);
+    pub const SEMICOLON: Self = Self(0x29);
+    pub const BACKSLASH: Self = Self(0x2A);
+    pub const COMMA: Self = Self(0x2B);
+    pub const SLASH: Self = Self(0x2C);
+    pub const N: Self = Self(0x2D);
+    pub const M: Self = Self(0x2E);
+    pub const PERIOD: Self = Self(0x2F);
+    pub const TAB: Self = Self(0x30);
+    pub const SPACE: Self = Self(0x31);
+    pub const GRAVE: Self = Self(0x32);
+    pub const DELETE: Self = Self(0x33);
+    pub const ESCAPE: Self = Self(0x35);
+    pub const F5: Self = Self(0x60);
+    pub const F6: Self = Self(0x61);
+    pub const F7: Self = Self(0x62);
+    pub const F3: Self = Self(0x63);
+    pub const F8: Self = Self(0x64);
+    pub const F9: Self = Self(0x65);
+    pub const F11: Self = Self(0x67);
+    pub const F10: Self = Self(0x6D);
+    pub const F12: Self = Self(0x6F);
+    pub const F4: Self = Self(0x76);
+    pub const F2: Self = Self(0x78);
+    pub const F1: Self = Self(0x7A);
+}
+
+/// A keyboard layout, used to find the character a physical key types.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Layout {
+    Us,
+    Uk,
+    German,
+    French,
+    Dvorak,
+}
+
+/// The keys that type a character, in the order of the layout tables below.
+const CHARACTER_KEYS: [KeyCode; 48] = [
+    KeyCode::A,
+    KeyCode::S,
+    KeyCode::D,
+    KeyCode::F,
+    KeyCode::H,
+    KeyCode::G,
+    KeyCode::Z,
+    KeyCode::X,
+    KeyCode::C,
+    KeyCode::V,
+    KeyCode::SECTION,
+    KeyCode::B,
+    KeyCode::Q,
+    KeyCode::W,
+    KeyCode::E,
+    KeyCode::R,
+    KeyCode::Y,
+    KeyCode::T,
+    KeyCode::NUM_1,
+    KeyCode::NUM_2,
+    KeyCode::NUM_3,
+    KeyCode::NUM_4,
+    KeyCode::NUM_6,
+    KeyCode::NUM_5,
+    KeyCode::EQUAL,
+    KeyCode::NUM_9,
+    KeyCode::NUM_7,
+    KeyCode::MINUS,
+    KeyCode::NUM_8,
+    KeyCode::NUM_0,
+    KeyCode::RIGHT_BRACKET,
+    KeyCode::O,
+    KeyCode::U,
+    KeyCode::LEFT_BRACKET,
+    KeyCode::I,
+    KeyCode::P,
+    KeyCode::L,
+    KeyCode::J,
+    KeyCode::QUOTE,
+    KeyCode::K,
+    KeyCode::SEMICOLON,
+    KeyCode::BACKSLASH,
+    KeyCode::COMMA,
+    KeyCode::SLASH,
+    KeyCode::N,
+    KeyCode::M,
+    KeyCode::PERIOD,
+    KeyCode::GRAVE,
+];
+
+// Unshifted characters of `CHARACTER_KEYS`, as typed with the macOS layouts of the same name.
+const US: &str = "asdfhgzxcv§bqwerty123465=97-80]ou[iplj'k;\\,/nm.`";
+const UK: &str = US;
+const GERMAN: &str = "asdfhgyxcv^bqwerzt123465´97ß80+ouüipljäkö#,-nm.<";
+const FRENCH: &str = "qsdfhgwxcv@bazerty&é\"'§(-çè)!à$ou^ipljùkm`;=n,:<";
+const DVORAK: &str = "aoeudi;qjk§x',.pfy123465]97[80=rg/clnh-ts\\wzbmv`";
+
+impl Layout {
+    /// The character typed by `key` without modifiers, `None` for keys that don't type a
+    /// character (return, tab, space, delete, escape and function keys are the same on all
+    /// layouts, see `Key::from_key_code(..)`).
+    pub fn character(self, key: KeyCode) -> Option<char> {
+        let index = CHARACTER_KEYS.iter().position(|other| *other == key)?;
+        self.table().chars().nth(index)
+    }
+
+    /// The physical key typing `character` without modifiers, if any.
+    pub fn key_code(self, character: char) -> Option<KeyCode> {
+        let index = self.table().chars().position(|other| other == character)?;
+        Some(CHARACTER_KEYS[index])
+    }
+
+    fn table(self) -> &'static str {
+        match self {
+            Layout::Us => US,
+            Layout::Uk => UK,
+            Layout::German => GERMAN,
+            Layout::French => FRENCH,
+            Layout::Dvorak => DVORAK,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUTS: [Layout; 5] = [
+        Layout::Us,
+        Layout::Uk,
+        Layout::German,
+        Layout::French,
+        Layout::Dvorak,
+    ];
+
+    #[test]
+    fn tables_cover_all_character_keys() {
+        for layout in LAYOUTS {
+            assert_eq!(
+                layout.table().chars().count(),
+                CHARACTER_KEYS.len(),
+                "{:?}",
+                layout
+            );
+        }
+    }
+
+    #[test]
+    fn characters_differ_between_layouts() {
+        assert_eq!(Layout::Us.character(KeyCode::Z), Some('z'));
+        assert_eq!(Layout::German.character(KeyCode::Z), Some('y'));
+        assert_eq!(Layout::German.character(KeyCode::Y), Some('z'));
+        assert_eq!(Layout::French.character(KeyCode::A), Some('q'));
+        assert_eq!(Layout::French.character(KeyCode::Q), Some('a'));
+        assert_eq!(Layout::French.character(KeyCode::NUM_1), Some('&'));
+        assert_eq!(Layout::Dvorak.character(KeyCode::S), Some('o'));
+    }
+
+    #[test]
+    fn key_code_inverts_character() {
+        for layout in LAYOUTS {
+            for key in CHARACTER_KEYS {
+                let character = layout.character(key).unwrap();
+                assert_eq!(
+                    layout.key_code(character),
+                    Some(key),
+                    "{:?} {:?}",
+                    layout,
+                    character
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn keys_without_characters() {
+        assert_eq!(Layout::Us.character(KeyCode::RETURN), None);
+        assert_eq!(Layout::Us.character(KeyCode::F1), None);
+        assert_eq!(Layout::German.key_code('€'), None);
+    }
+}
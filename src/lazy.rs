@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+#[cfg(target_os = "macos")]
+use std::sync::Arc;
+
+#[cfg(target_os = "macos")]
+use crate::MenuItem;
+
+// ----------------------------------------------------------------------------
+
+#[cfg(target_os = "macos")]
+pub(crate) type LazyProvider = Arc<dyn Fn() -> Vec<MenuItem> + Send + Sync + 'static>;
+
+/// When the provider of a lazy submenu is called, see `MenuItem::lazy_sub_menu(..)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LazyPolicy {
+    /// The first time the submenu is about to open, its items are kept afterwards.
+    Once,
+    /// Every time the submenu is about to open.
+    Always,
+}
+
+/// Population state of one realized lazy submenu.
+#[derive(Debug)]
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+pub(crate) struct LazyState {
+    policy: LazyPolicy,
+    populated: AtomicBool,
+}
+
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+impl LazyState {
+    pub(crate) fn new(policy: LazyPolicy) -> Self {
+        Self {
+            policy,
+            populated: AtomicBool::new(false),
+        }
+    }
+
+    /// Called every time the submenu is about to open, returns whether the provider should be
+    /// called.
+    pub(crate) fn begin_update(&self) -> bool {
+        match self.policy {
+            LazyPolicy::Once => !self.populated.swap(true, Ordering::AcqRel),
+            LazyPolicy::Always => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn once_updates_only_the_first_time() {
+        let state = LazyState::new(LazyPolicy::Once);
+
+        assert!(state.begin_update());
+        assert!(!state.begin_update());
+        assert!(!state.begin_update());
+    }
+
+    #[test]
+    fn always_updates_every_time() {
+        let state = LazyState::new(LazyPolicy::Always);
+
+        assert!(state.begin_update());
+        assert!(state.begin_update());
+        assert!(state.begin_update());
+    }
+
+    #[test]
+    fn states_are_independent() {
+        let first = LazyState::new(LazyPolicy::Once);
+        let second = LazyState::new(LazyPolicy::Once);
+
+        assert!(first.begin_update());
+        assert!(second.begin_update());
+    }
+}
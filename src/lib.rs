@@ -1,28 +1,110 @@
+#[cfg(target_os = "macos")]
 use icrate::AppKit::{NSApplication, NSMenuItem};
+#[cfg(target_os = "macos")]
 use objc2::rc::autoreleasepool;
+#[cfg(target_os = "macos")]
 use objc2::runtime;
 
 #[cfg(target_os = "macos")]
 mod item;
-pub use item::{Action, MenuItem};
 #[cfg(target_os = "macos")]
+pub use item::{Action, ItemContext, MenuItem, MenuItemState, Validation};
 mod shortcut;
-pub use shortcut::{Key, Shortcut};
+pub use shortcut::{ChordShortcut, Key, Modifiers, ParseShortcutError, Shortcut};
 #[cfg(target_os = "macos")]
 mod menu;
+#[cfg(target_os = "macos")]
 pub use menu::Menu;
 #[cfg(target_os = "macos")]
 mod menubar;
+#[cfg(target_os = "macos")]
 pub use menubar::MenuBar;
 #[cfg(target_os = "macos")]
 mod image;
+#[cfg(target_os = "macos")]
 pub use image::Image;
+#[cfg(target_os = "macos")]
+mod status_item;
+#[cfg(target_os = "macos")]
+pub use status_item::StatusItem;
+#[cfg(target_os = "macos")]
+mod dock;
+#[cfg(target_os = "macos")]
+pub use dock::{set_dock_menu, set_dock_menu_provider};
+#[cfg(target_os = "macos")]
+mod popup;
+#[cfg(target_os = "macos")]
+pub use popup::Popup;
+#[cfg(target_os = "macos")]
+mod delegate;
+#[cfg(target_os = "macos")]
+mod direction;
+#[cfg(target_os = "macos")]
+pub use direction::LayoutDirection;
+mod lazy;
+pub use lazy::LazyPolicy;
+mod alternate;
+pub use alternate::AlternateError;
+mod conflicts;
+pub use conflicts::ShortcutConflict;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod keymap;
+pub use keymap::{Keymap, KeymapError, KeymapReport};
+mod chord;
+pub use chord::{ChordEvent, ChordMatcher};
+mod recorder;
+pub use recorder::{RecorderEvent, ShortcutRecorder};
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod localize;
+#[cfg(target_os = "macos")]
+pub use localize::set_localizer;
+pub use localize::{LocalizedTitle, Localizer};
+#[cfg(feature = "fluent")]
+mod fluent;
+#[cfg(feature = "fluent")]
+pub use fluent::{FluentError, FluentLocalizer};
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod hotkey;
+#[cfg(target_os = "macos")]
+pub use hotkey::{register_hotkey, registered_hotkeys, set_hotkey_layout, unregister_hotkey};
+pub use hotkey::{HotKeyError, HotKeyId};
+#[cfg(target_os = "macos")]
+mod carbon;
+mod rich_text;
+pub use rich_text::{Color, RichText, TextRun};
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod palette;
+pub use palette::SelectionMode;
+#[cfg(target_os = "macos")]
+mod badge;
+#[cfg_attr(not(target_os = "macos"), allow(dead_code))]
+mod capabilities;
+#[cfg(target_os = "macos")]
+pub use badge::Badge;
+#[cfg(target_os = "macos")]
+mod view;
+#[cfg(target_os = "macos")]
+pub use view::ItemView;
+mod recent;
+pub use recent::RecentItemsMenu;
+mod layout;
+pub use layout::{KeyCode, Layout};
+mod window_list;
+pub use window_list::{WindowEntry, WindowListMenu, WindowMark, WindowRow};
 
 // ----------------------------------------------------------------------------
 
+/// `NSUInteger`, for modifier masks in modules that also build without AppKit.
+#[cfg(target_os = "macos")]
+pub(crate) use objc2::ffi::NSUInteger;
+#[cfg(not(target_os = "macos"))]
+pub(crate) type NSUInteger = usize;
+
+#[cfg(target_os = "macos")]
 #[allow(non_camel_case_types)]
 pub(crate) type id = *mut runtime::NSObject;
 
+#[cfg(target_os = "macos")]
 #[allow(non_upper_case_globals)]
 pub(crate) const nil: id = 0 as id;
 
@@ -43,24 +125,28 @@ pub fn activate_menubar(menubar: &MenuBar) {
             let main_menu = app.mainMenu().unwrap();
             let num_items = main_menu.numberOfItems();
 
-            if menubar.main_menu.is_some() {
-                main_menu.removeAllItems();
+            direction::realize_with(menubar.direction, || {
+                direction::apply(&main_menu);
+
+                if menubar.main_menu.is_some() {
+                    main_menu.removeAllItems();
 
-                let item = NSMenuItem::new();
-                item.setSubmenu(Some(&menubar.main_menu.as_ref().unwrap().to_objc()));
-                main_menu.addItem(&item);
-            } else {
-                // Remove all items except the main menu
-                for i in 1..num_items {
-                    main_menu.removeItemAtIndex(i);
+                    let item = NSMenuItem::new();
+                    item.setSubmenu(Some(&menubar.main_menu.as_ref().unwrap().to_objc()));
+                    main_menu.addItem(&item);
+                } else {
+                    // Remove all items except the main menu
+                    for i in 1..num_items {
+                        main_menu.removeItemAtIndex(i);
+                    }
                 }
-            }
 
-            for menu in menubar.menus.iter() {
-                let item = NSMenuItem::new();
-                item.setSubmenu(Some(&menu.to_objc()));
-                main_menu.addItem(&item);
-            }
+                for menu in menubar.menus.iter() {
+                    let item = NSMenuItem::new();
+                    item.setSubmenu(Some(&menu.to_objc()));
+                    main_menu.addItem(&item);
+                }
+            });
         });
     }
 }
@@ -0,0 +1,355 @@
+use std::{cell::RefCell, rc::Rc};
+
+#[cfg(target_os = "macos")]
+use icrate::{
+    AppKit::{NSApplication, NSMenu, NSMenuItem},
+    Foundation::NSString,
+};
+#[cfg(target_os = "macos")]
+use libc::c_void;
+#[cfg(target_os = "macos")]
+use objc2::{
+    class,
+    declare::ClassBuilder,
+    ffi, msg_send,
+    rc::Id,
+    runtime::{AnyClass, NSObject, Sel},
+    sel,
+};
+#[cfg(target_os = "macos")]
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "macos")]
+use crate::{id, item::load};
+use crate::{Color, RichText, TextRun};
+
+// ----------------------------------------------------------------------------
+
+/// Translates the keys of `LocalizedTitle`s, see `set_localizer(..)`.
+pub trait Localizer {
+    /// The text for `key` with `args` filled in, `None` if there's no translation.
+    fn localize(&self, key: &str, args: &[(String, String)]) -> Option<String>;
+}
+
+/// A title that is translated by the current `Localizer` every time it's realized, see
+/// `MenuItem::localized_title(..)` and `Menu::localized_title(..)`.
+///
+/// The key is shown if there's no localizer or no translation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalizedTitle {
+    key: String,
+    args: Vec<(String, String)>,
+}
+
+impl LocalizedTitle {
+    pub fn new(key: &str) -> Self {
+        Self {
+            key: key.to_string(),
+            args: Vec::new(),
+        }
+    }
+
+    /// Adds an argument, e.g. `arg("count", 3)` for "{ $count } files" in Fluent.
+    pub fn arg(mut self, name: &str, value: impl ToString) -> Self {
+        self.args.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn args(&self) -> &[(String, String)] {
+        &self.args
+    }
+
+    /// The title translated by the current localizer.
+    pub fn resolve(&self) -> String {
+        LOCALIZER
+            .with(|localizer| {
+                let localizer = localizer.borrow();
+                localizer.as_ref()?.localize(&self.key, &self.args)
+            })
+            .unwrap_or_else(|| self.key.clone())
+    }
+}
+
+thread_local! {
+    static LOCALIZER: RefCell<Option<Rc<dyn Localizer>>> = const { RefCell::new(None) };
+}
+
+/// Sets the localizer used to realize localized titles, `None` shows the keys.
+///
+/// The titles of the app's menu bar are translated again right away, without rebuilding it. Other
+/// realized menus, e.g. of a `StatusItem`, are translated the next time they're realized.
+///
+/// # Warning
+/// - This will panic if no shared application exists.
+/// - Must be called on the main thread, localizers are per thread.
+#[cfg(target_os = "macos")]
+pub fn set_localizer(localizer: Option<Box<dyn Localizer>>) {
+    LOCALIZER.with(|current| *current.borrow_mut() = localizer.map(Rc::from));
+
+    unsafe {
+        let app = NSApplication::sharedApplication();
+        if let Some(main_menu) = app.mainMenu() {
+            relocalize(&main_menu);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// What a realized item shows besides its title text, kept so the title can be built again for
+/// another localizer.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct TitleDecorations {
+    /// Appended to the title on systems without native badges, see `Badge::text()`.
+    pub(crate) badge: Option<String>,
+    /// Replaces the title, see `MenuItem::attributed_title(..)`.
+    pub(crate) attributed_title: Option<RichText>,
+    /// Shown after the title, see `MenuItem::chord(..)`.
+    pub(crate) chord: Option<String>,
+}
+
+impl TitleDecorations {
+    /// The plain title for `text`.
+    pub(crate) fn title(&self, text: &str) -> String {
+        match self.badge {
+            Some(ref badge) => format!("{} ({})", text, badge),
+            None => text.to_string(),
+        }
+    }
+
+    /// The attributed title for `text`, `None` if the plain title is enough.
+    pub(crate) fn attributed_title(&self, text: &str) -> Option<RichText> {
+        let attributed_title = match (&self.attributed_title, &self.badge, &self.chord) {
+            (Some(attributed_title), Some(badge), _) => attributed_title
+                .clone()
+                .push(TextRun::new(&format!(" ({})", badge))),
+            (Some(attributed_title), None, _) => attributed_title.clone(),
+            (None, _, Some(_)) => RichText::from(self.title(text).as_str()),
+            (None, _, None) => return None,
+        };
+
+        Some(match self.chord {
+            Some(ref chord) => attributed_title
+                .push(TextRun::new(&format!("    {}", chord)).color(Some(Color::SecondaryLabel))),
+            None => attributed_title,
+        })
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl TitleDecorations {
+    /// Shows `text` with these decorations on `item`.
+    pub(crate) fn apply(&self, item: &NSMenuItem, text: &str) {
+        unsafe {
+            item.setTitle(&NSString::from_str(&self.title(text)));
+            if let Some(attributed_title) = self.attributed_title(text) {
+                item.setAttributedTitle(Some(&attributed_title.to_objc()));
+            }
+        }
+    }
+}
+
+/// A localized title as realized on a menu or item, see `associate(..)`.
+#[cfg(target_os = "macos")]
+#[derive(Debug, Clone)]
+pub(crate) struct RealizedTitle {
+    pub(crate) title: LocalizedTitle,
+    pub(crate) decorations: TitleDecorations,
+}
+
+#[cfg(target_os = "macos")]
+impl RealizedTitle {
+    /// Shows the title translated by the current localizer on `item` and its submenu.
+    pub(crate) fn apply(&self, item: &NSMenuItem) {
+        let text = self.title.resolve();
+        self.decorations.apply(item, &text);
+
+        unsafe {
+            if let Some(submenu) = item.submenu() {
+                submenu.setTitle(&NSString::from_str(&text));
+            }
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(target_os = "macos")]
+static TITLE_PTR: &str = "cacaoLocalizedTitlePtr";
+#[cfg(target_os = "macos")]
+static TITLE_KEY: u8 = 0;
+
+/// Remembers `title` on a realized menu or item, so it can be translated again, see
+/// `set_localizer(..)`.
+///
+/// The title is kept by an object attached to `object` as an associated object, so it lives as
+/// long as `object` does.
+#[cfg(target_os = "macos")]
+pub(crate) fn associate(object: *mut ffi::objc_object, title: RealizedTitle) {
+    let ptr = Box::into_raw(Box::new(title));
+
+    unsafe {
+        let holder: id = msg_send![register_title_class(), new];
+        (&mut *holder).set_ivar(TITLE_PTR, ptr as usize);
+
+        ffi::objc_setAssociatedObject(
+            object,
+            &TITLE_KEY as *const u8 as *const c_void,
+            holder as *mut ffi::objc_object,
+            ffi::OBJC_ASSOCIATION_RETAIN_NONATOMIC,
+        );
+        let _: () = msg_send![holder, release];
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn associated(object: *mut ffi::objc_object) -> Option<RealizedTitle> {
+    unsafe {
+        let holder =
+            ffi::objc_getAssociatedObject(object, &TITLE_KEY as *const u8 as *const c_void);
+        let holder = (holder as *const NSObject).as_ref()?;
+        Some(load::<RealizedTitle>(holder, TITLE_PTR).clone())
+    }
+}
+
+#[cfg(target_os = "macos")]
+extern "C" fn dealloc_cacao_localized_title(this: &NSObject, _: Sel) {
+    unsafe {
+        let ptr: usize = *this.ivar(TITLE_PTR);
+        let obj = ptr as *mut RealizedTitle;
+
+        if !obj.is_null() {
+            let _title = Box::from_raw(obj);
+        }
+
+        let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn register_title_class() -> &'static AnyClass {
+    static CLASS: Lazy<&'static AnyClass> = Lazy::new(|| unsafe {
+        let mut builder = ClassBuilder::new("CacaoLocalizedTitle", class!(NSObject)).unwrap();
+
+        builder.add_ivar::<usize>(TITLE_PTR);
+
+        builder.add_method(
+            sel!(dealloc),
+            dealloc_cacao_localized_title as unsafe extern "C" fn(_, _),
+        );
+
+        builder.register()
+    });
+
+    &CLASS
+}
+
+/// Translates the localized titles of `menu`, its items and submenus again.
+#[cfg(target_os = "macos")]
+fn relocalize(menu: &NSMenu) {
+    unsafe {
+        if let Some(title) = associated(menu as *const NSMenu as *mut ffi::objc_object) {
+            menu.setTitle(&NSString::from_str(&title.title.resolve()));
+        }
+
+        for item in menu.itemArray().iter() {
+            // Submenus first, an item's own title replaces the title of its submenu
+            if let Some(submenu) = item.submenu() {
+                relocalize(&submenu);
+            }
+
+            match associated(item as *const _ as *mut ffi::objc_object) {
+                Some(title) => title.apply(item),
+                // Top-level items show the title of their submenu
+                None => {
+                    if let Some(submenu) = item.submenu() {
+                        let submenu_ptr = Id::as_ptr(&submenu) as *mut ffi::objc_object;
+                        if associated(submenu_ptr).is_some() {
+                            item.setTitle(&submenu.title());
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decorations(
+        badge: Option<&str>,
+        attributed_title: Option<RichText>,
+        chord: Option<&str>,
+    ) -> TitleDecorations {
+        TitleDecorations {
+            badge: badge.map(str::to_string),
+            attributed_title,
+            chord: chord.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn plain_title_gets_badge_fallback() {
+        assert_eq!(TitleDecorations::default().title("Inbox"), "Inbox");
+        assert_eq!(
+            decorations(Some("3 new"), None, None).title("Inbox"),
+            "Inbox (3 new)"
+        );
+        assert_eq!(
+            decorations(Some("3 new"), None, None).attributed_title("Inbox"),
+            None
+        );
+    }
+
+    #[test]
+    fn chord_is_appended_to_the_translated_title() {
+        let title = decorations(Some("2"), None, Some("⌘K ⌘C")).attributed_title("Kommentar");
+
+        assert_eq!(
+            title.map(|title| title.plain_text()).as_deref(),
+            Some("Kommentar (2)    ⌘K ⌘C")
+        );
+    }
+
+    #[test]
+    fn attributed_title_replaces_the_text() {
+        let rich = RichText::from("Bold");
+        let title = decorations(Some("1"), Some(rich), None).attributed_title("ignored");
+
+        assert_eq!(
+            title.map(|title| title.plain_text()).as_deref(),
+            Some("Bold (1)")
+        );
+    }
+
+    #[test]
+    fn resolve_shows_key_without_localizer() {
+        let title = LocalizedTitle::new("file-menu").arg("count", 3);
+
+        assert_eq!(title.resolve(), "file-menu");
+        assert_eq!(title.args(), [(String::from("count"), String::from("3"))]);
+    }
+
+    #[test]
+    fn resolve_uses_current_localizer() {
+        struct Upper;
+
+        impl Localizer for Upper {
+            fn localize(&self, key: &str, _args: &[(String, String)]) -> Option<String> {
+                (key != "missing").then(|| key.to_uppercase())
+            }
+        }
+
+        LOCALIZER.with(|current| *current.borrow_mut() = Some(Rc::new(Upper)));
+        assert_eq!(LocalizedTitle::new("edit").resolve(), "EDIT");
+        assert_eq!(LocalizedTitle::new("missing").resolve(), "missing");
+        LOCALIZER.with(|current| *current.borrow_mut() = None);
+    }
+}
@@ -1,26 +1,135 @@
-use icrate::AppKit::{NSEvent, NSMenu};
-use icrate::Foundation::{CGFloat, CGPoint, NSString};
-use objc2::rc::{autoreleasepool, Id};
-use objc2::ClassType;
+use std::any::Any;
+use std::sync::{Arc, Mutex};
 
-use crate::item::MenuItem;
+use icrate::AppKit::NSMenu;
+use icrate::Foundation::{NSInteger, NSString};
+use objc2::rc::Id;
+use objc2::{ffi, msg_send, ClassType};
+
+use crate::alternate::{check_alternates, AlternateError};
+use crate::capabilities::{Capabilities, PaletteStyle};
+use crate::chord::ChordMatcher;
+use crate::conflicts::ShortcutEntry;
+use crate::delegate::MenuEvents;
+use crate::direction::{self, LayoutDirection};
+use crate::item::{Action, ChosenHook, MenuItem};
+use crate::keymap::Keymap;
+use crate::localize::{associate, LocalizedTitle, RealizedTitle};
+use crate::palette::{Palette, SelectionMode};
+use crate::popup::{AppKitPopup, PopupBackend};
+use crate::Popup;
 
 // ----------------------------------------------------------------------------
 
 #[derive(Clone)]
 pub struct Menu {
     title: String,
+    localized_title: Option<LocalizedTitle>,
     items: Vec<MenuItem>,
+    events: MenuEvents,
+    autoenables_items: bool,
+    direction: LayoutDirection,
 }
 
 impl Menu {
     pub fn new(title: &str, items: Vec<MenuItem>) -> Self {
         Self {
             title: title.to_string(),
+            localized_title: None,
             items,
+            events: MenuEvents::default(),
+            autoenables_items: false,
+            direction: LayoutDirection::Inherit,
         }
     }
 
+    /// Title translated by the current `Localizer` when the menu is realized, shown instead of
+    /// the plain title.
+    pub fn localized_title(self, localized_title: Option<LocalizedTitle>) -> Self {
+        Self {
+            localized_title,
+            ..self
+        }
+    }
+
+    /// Shows the items as a compact row of selectable items, e.g. color tags. Only items with an
+    /// id can be selected, `selected` holds the initially selected ids.
+    ///
+    /// `on_change` is called with the selected ids every time an item is chosen.
+    ///
+    /// # Info
+    /// Palette menus require macOS 14, on older systems the items are shown as ordinary items
+    /// with a checkmark next to the selected ones.
+    pub fn palette<F>(mut self, mode: SelectionMode, selected: Vec<String>, on_change: F) -> Self
+    where
+        F: Fn(&[String]) + Send + Sync + 'static,
+    {
+        self.events.palette = Some(Palette::new(mode, selected, Arc::new(on_change)));
+        self
+    }
+
+    /// Lets AppKit enable and disable items whose action is sent through the responder chain,
+    /// e.g. `Action::CloseWindow` is disabled if there's no window to close.
+    ///
+    /// # Info
+    /// When enabled, `MenuItem::enabled(..)` is ignored for such items, and so is the `enabled`
+    /// field returned by their validator. Items with an `Action::Callback` keep using their
+    /// validator, see `MenuItem::validate(..)`.
+    pub fn autoenables_items(self, autoenables_items: bool) -> Self {
+        Self {
+            autoenables_items,
+            ..self
+        }
+    }
+
+    /// Lays out the menu and its submenus right to left or left to right, e.g. right to left for
+    /// Arabic and Hebrew. By default a menu has the direction of its parent menu, top-level
+    /// menus have the direction of the app.
+    ///
+    /// Images are only mirrored if they ask for it, see `Image::mirror_in_rtl(..)`.
+    pub fn layout_direction(self, direction: LayoutDirection) -> Self {
+        Self { direction, ..self }
+    }
+
+    /// Called right before the menu opens.
+    pub fn on_will_open<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.events.will_open = Some(Arc::new(f));
+        self
+    }
+
+    /// Called after the menu is closed.
+    pub fn on_did_close<F>(mut self, f: F) -> Self
+    where
+        F: Fn() + Send + Sync + 'static,
+    {
+        self.events.did_close = Some(Arc::new(f));
+        self
+    }
+
+    /// Called when the highlighted item changes, with the id of the newly highlighted item.
+    ///
+    /// The id is `None` if no item is highlighted or the item has no id.
+    pub fn on_highlight<F>(mut self, f: F) -> Self
+    where
+        F: Fn(Option<&str>) + Send + Sync + 'static,
+    {
+        self.events.highlight = Some(Arc::new(f));
+        self
+    }
+
+    /// Called when the menu is about to be displayed. Return new items to replace the menu's
+    /// content, or `None` to keep it.
+    pub fn on_needs_update<F>(mut self, f: F) -> Self
+    where
+        F: Fn() -> Option<Vec<MenuItem>> + Send + Sync + 'static,
+    {
+        self.events.needs_update = Some(Arc::new(f));
+        self
+    }
+
     pub fn add_item(&mut self, item: &MenuItem) {
         self.items.push(item.clone());
     }
@@ -33,33 +142,332 @@ impl Menu {
 
     /// Shows a popup of this menu at the current mouse position.
     pub fn show_popup(&self) {
-        let pos = unsafe { NSEvent::mouseLocation() };
-        self.show_popup_at([pos.x as u32, pos.y as u32]);
+        self.popup(Popup::at_mouse());
     }
 
     /// Shows a popup of this menu at the given position.
     pub fn show_popup_at(&self, position: [u32; 2]) {
-        let point = CGPoint::new(position[0] as CGFloat, position[1] as CGFloat);
-        unsafe {
-            autoreleasepool(|_| {
-                let menu = self.to_objc();
-                menu.popUpMenuPositioningItem_atLocation_inView(None, point, None);
-            });
+        self.popup(Popup::at([position[0] as f64, position[1] as f64]));
+    }
+
+    /// Shows a popup of this menu, blocking until the menu is closed.
+    ///
+    /// Returns `true` if an item was chosen, `false` if the menu was dismissed.
+    pub fn popup(&self, popup: Popup) -> bool {
+        AppKitPopup.show(self, &popup)
+    }
+
+    /// Shows a popup of this menu, blocking until the menu is closed.
+    ///
+    /// Returns the id of the chosen item, `None` if the menu was dismissed or the chosen item has
+    /// no id. The action of the chosen item is still performed.
+    pub fn popup_select(&self, popup: Popup) -> Option<String> {
+        self.popup_select_with(&mut AppKitPopup, popup)
+    }
+
+    pub(crate) fn popup_select_with<B: PopupBackend>(
+        &self,
+        backend: &mut B,
+        popup: Popup,
+    ) -> Option<String> {
+        let selected = Arc::new(Mutex::new(None));
+        let hook: ChosenHook = {
+            let selected = selected.clone();
+            Arc::new(move |id| *selected.lock().unwrap() = Some(id.to_string()))
+        };
+        let menu = self.hook_chosen(&hook);
+
+        if !backend.show(&menu, &popup) {
+            return None;
+        }
+
+        let id = selected.lock().unwrap().take();
+        id
+    }
+
+    /// Copy of this menu that calls `hook` with the id of the chosen item, including items in
+    /// submenus.
+    pub(crate) fn hook_chosen(&self, hook: &ChosenHook) -> Self {
+        let mut events = self.events.clone();
+        // Items swapped in when the menu opens are hooked as well
+        if let Some(needs_update) = events.needs_update.take() {
+            let hook = hook.clone();
+            events.needs_update = Some(Arc::new(move || {
+                let items = needs_update()?;
+                Some(
+                    items
+                        .iter()
+                        .map(|item| item.hook_chosen(&hook, true))
+                        .collect(),
+                )
+            }));
+        }
+
+        Self {
+            items: self
+                .items
+                .iter()
+                .map(|item| item.hook_chosen(hook, true))
+                .collect(),
+            events,
+            ..self.clone()
+        }
+    }
+
+    /// The first item with the given id in this menu or its submenus.
+    pub fn item_with_id(&self, id: &str) -> Option<&MenuItem> {
+        self.find_item(&|item| item.get_id() == Some(id))
+    }
+
+    /// The first item with the given tag in this menu or its submenus.
+    pub fn item_with_tag(&self, tag: isize) -> Option<&MenuItem> {
+        self.find_item(&|item| item.get_tag() == Some(tag))
+    }
+
+    /// The first item whose represented value equals `represented` in this menu or its submenus.
+    pub fn item_with_represented<T>(&self, represented: &T) -> Option<&MenuItem>
+    where
+        T: Any + PartialEq,
+    {
+        self.find_item(&|item| item.get_represented::<T>() == Some(represented))
+    }
+
+    fn find_item(&self, predicate: &dyn Fn(&MenuItem) -> bool) -> Option<&MenuItem> {
+        self.items.iter().find_map(|item| {
+            if predicate(item) {
+                Some(item)
+            } else {
+                item.submenu()?.find_item(predicate)
+            }
+        })
+    }
+
+    /// The shortcuts of the items of this menu and its submenus, with paths below `path`.
+    pub(crate) fn shortcut_entries(&self, path: &[String], entries: &mut Vec<ShortcutEntry>) {
+        let mut path = path.to_vec();
+        path.push(self.title.clone());
+
+        for item in self.items.iter() {
+            item.shortcut_entries(&path, entries);
+        }
+    }
+
+    pub(crate) fn keymap_entries(&self, path: &[String], keymap: &mut Keymap) {
+        let mut path = path.to_vec();
+        path.push(self.title.clone());
+
+        for item in self.items.iter() {
+            item.keymap_entries(&path, keymap);
+        }
+    }
+
+    pub(crate) fn apply_keymap(
+        &mut self,
+        path: &[String],
+        keymap: &Keymap,
+        matched: &mut Vec<String>,
+    ) {
+        let mut path = path.to_vec();
+        path.push(self.title.clone());
+
+        for item in self.items.iter_mut() {
+            item.apply_keymap(&path, keymap, matched);
+        }
+    }
+
+    pub(crate) fn chord_entries(&self, matcher: &mut ChordMatcher<Action>) {
+        for item in self.items.iter() {
+            item.chord_entries(matcher);
+        }
+    }
+
+    /// Checks that the alternate items of this menu and its submenus will be revealed by AppKit,
+    /// see `MenuItem::alternate(..)`.
+    pub fn check_alternates(&self) -> Result<(), AlternateError> {
+        let key_equivalents: Vec<_> = self
+            .items
+            .iter()
+            .flat_map(MenuItem::key_equivalents)
+            .collect();
+        check_alternates(&key_equivalents)?;
+
+        for item in self.items.iter() {
+            if let Some(menu) = item.submenu() {
+                menu.check_alternates()?;
+            }
         }
+
+        Ok(())
     }
 
     pub(crate) fn to_objc(&self) -> Id<NSMenu> {
+        direction::realize_with(self.direction, || self.realize())
+    }
+
+    fn realize(&self) -> Id<NSMenu> {
         unsafe {
             let alloc = NSMenu::alloc();
-            let title = NSString::from_str(&self.title);
+            let title = match self.localized_title {
+                Some(ref localized_title) => NSString::from_str(&localized_title.resolve()),
+                None => NSString::from_str(&self.title),
+            };
             let menu = NSMenu::initWithTitle(alloc, &title);
-            menu.setAutoenablesItems(false);
+            if let Some(ref localized_title) = self.localized_title {
+                let title = RealizedTitle {
+                    title: localized_title.clone(),
+                    decorations: Default::default(),
+                };
+                associate(Id::as_ptr(&menu) as *mut ffi::objc_object, title);
+            }
+            menu.setAutoenablesItems(self.autoenables_items);
+            direction::apply(&menu);
+
+            if let Some(ref palette) = self.events.palette {
+                let hook: ChosenHook = {
+                    let palette = palette.clone();
+                    Arc::new(move |id| palette.choose(id))
+                };
+                for item in self.items.iter() {
+                    item.hook_chosen(&hook, false).add_to(&menu);
+                }
+
+                if Capabilities::current().palette_style() == PaletteStyle::Native {
+                    let _: () = msg_send![&*menu, setPresentationStyle: 1 as NSInteger];
+                    let _: () = msg_send![&*menu, setSelectionMode: palette.mode.to_objc()];
+                }
+                palette.apply(&menu);
+            } else {
+                for item in self.items.iter() {
+                    item.add_to(&menu);
+                }
+            }
 
-            for item in self.items.iter() {
-                menu.addItem(&item.to_objc());
+            if !self.events.is_empty() || self.items.iter().any(MenuItem::has_validator) {
+                self.events.attach(&menu);
             }
 
             menu
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    /// Chooses the item with an id, after updating the menu as AppKit does when it opens.
+    struct FakePopup {
+        choose: Option<&'static str>,
+    }
+
+    impl PopupBackend for FakePopup {
+        fn show(&mut self, menu: &Menu, _popup: &Popup) -> bool {
+            let needs_update = menu.events.needs_update.as_ref();
+            let items = needs_update.and_then(|needs_update| needs_update());
+            let menu = Menu {
+                items: items.unwrap_or_else(|| menu.items.clone()),
+                ..menu.clone()
+            };
+
+            let chosen = self.choose.and_then(|id| menu.item_with_id(id));
+            match chosen.and_then(MenuItem::chosen_action) {
+                Some(action) => {
+                    action.perform();
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    fn popup_select(menu: &Menu, choose: Option<&'static str>) -> Option<String> {
+        menu.popup_select_with(&mut FakePopup { choose }, Popup::at([0.0, 0.0]))
+    }
+
+    fn counting_button(id: &str, count: &Arc<AtomicUsize>) -> MenuItem {
+        let count = count.clone();
+        let action = Action::callback(move || {
+            count.fetch_add(1, Ordering::SeqCst);
+        });
+        MenuItem::button(id.to_string(), action, None).id(id)
+    }
+
+    #[test]
+    fn popup_select_returns_chosen_id() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let items = vec![counting_button("a", &count), counting_button("b", &count)];
+        let menu = Menu::new("", items);
+
+        assert_eq!(popup_select(&menu, Some("b")).as_deref(), Some("b"));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn popup_select_returns_none_when_dismissed() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let menu = Menu::new("", vec![counting_button("a", &count)]);
+
+        assert_eq!(popup_select(&menu, None), None);
+        assert_eq!(count.load(Ordering::SeqCst), 0);
+    }
+
+    #[test]
+    fn popup_select_finds_items_in_submenus() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let submenu = Menu::new("Sub", vec![counting_button("nested", &count)]);
+        let menu = Menu::new("", vec![MenuItem::sub_menu(submenu)]);
+
+        assert_eq!(
+            popup_select(&menu, Some("nested")).as_deref(),
+            Some("nested")
+        );
+    }
+
+    #[test]
+    fn popup_select_hooks_updated_items() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let menu = {
+            let count = count.clone();
+            Menu::new("", Vec::new())
+                .on_needs_update(move || Some(vec![counting_button("updated", &count)]))
+        };
+
+        assert_eq!(
+            popup_select(&menu, Some("updated")).as_deref(),
+            Some("updated")
+        );
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn popup_select_keeps_item_context() {
+        let tag = Arc::new(Mutex::new(None));
+        let action = {
+            let tag = tag.clone();
+            Action::callback_with_context(move |context| {
+                *tag.lock().unwrap() = context.tag();
+            })
+        };
+        let item = MenuItem::button("a".to_string(), action, None)
+            .id("a")
+            .tag(7);
+        let menu = Menu::new("", vec![item]);
+
+        assert_eq!(popup_select(&menu, Some("a")).as_deref(), Some("a"));
+        assert_eq!(*tag.lock().unwrap(), Some(7));
+    }
+
+    #[test]
+    fn item_with_tag_skips_untagged_items() {
+        let untagged = MenuItem::button("a".to_string(), Action::None, None).id("a");
+        let tagged = MenuItem::button("b".to_string(), Action::None, None)
+            .id("b")
+            .tag(0);
+        let menu = Menu::new("", vec![untagged, tagged]);
+
+        assert_eq!(menu.item_with_tag(0).and_then(MenuItem::get_id), Some("b"));
+        assert!(menu.item_with_tag(1).is_none());
+    }
+}
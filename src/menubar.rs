@@ -1,10 +1,12 @@
-use crate::Menu;
+use crate::conflicts::{find_conflicts, ShortcutConflict};
+use crate::{LayoutDirection, Menu};
 
 // ----------------------------------------------------------------------------
 
 pub struct MenuBar {
     pub(crate) main_menu: Option<Menu>,
     pub(crate) menus: Vec<Menu>,
+    pub(crate) direction: LayoutDirection,
 }
 
 impl MenuBar {
@@ -12,10 +14,32 @@ impl MenuBar {
         Self {
             main_menu: None, // Keep the default
             menus,
+            direction: LayoutDirection::Inherit,
         }
     }
 
     pub fn main_menu(self, main_menu: Option<Menu>) -> Self {
         Self { main_menu, ..self }
     }
+
+    /// The layout direction of all menus that don't set their own, see
+    /// `Menu::layout_direction(..)`. By default the direction of the app.
+    pub fn layout_direction(self, direction: LayoutDirection) -> Self {
+        Self { direction, ..self }
+    }
+
+    /// Finds shortcuts used by several items and shortcuts reserved by the system, in all menus
+    /// and their submenus.
+    ///
+    /// Shortcuts are compared the way AppKit matches them, e.g. `Key::Other("E")` with command
+    /// is the same as `Key::E` with shift and command. Standard items using their own shortcut,
+    /// like `Action::Quit` with ⌘Q, aren't reported. Lazy submenus aren't checked.
+    pub fn shortcut_conflicts(&self) -> Vec<ShortcutConflict> {
+        let mut entries = Vec::new();
+        for menu in self.main_menu.iter().chain(self.menus.iter()) {
+            menu.shortcut_entries(&[], &mut entries);
+        }
+
+        find_conflicts(&entries)
+    }
 }
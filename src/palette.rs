@@ -0,0 +1,199 @@
+use std::sync::{Arc, Mutex};
+
+#[cfg(target_os = "macos")]
+use icrate::{
+    AppKit::{NSControlStateValueOff, NSControlStateValueOn, NSMenu},
+    Foundation::{NSInteger, NSString},
+};
+#[cfg(target_os = "macos")]
+use objc2::msg_send;
+
+// ----------------------------------------------------------------------------
+
+/// How items of a palette menu are selected, see `Menu::palette(..)`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionMode {
+    /// Choosing an item selects it and deselects the others.
+    One,
+    /// Choosing an item toggles its selection.
+    Any,
+}
+
+#[cfg(target_os = "macos")]
+impl SelectionMode {
+    /// Same as `NSMenuSelectionMode`.
+    pub(crate) fn to_objc(self) -> NSInteger {
+        match self {
+            SelectionMode::One => 1,
+            SelectionMode::Any => 2,
+        }
+    }
+}
+
+type SelectionCallback = Arc<dyn Fn(&[String]) + Send + Sync + 'static>;
+
+/// Selected item ids of a palette menu, shared between all realizations of the menu.
+#[derive(Clone)]
+pub(crate) struct Palette {
+    pub(crate) mode: SelectionMode,
+    selected: Arc<Mutex<Vec<String>>>,
+    on_change: SelectionCallback,
+}
+
+impl Palette {
+    pub(crate) fn new(
+        mode: SelectionMode,
+        selected: Vec<String>,
+        on_change: SelectionCallback,
+    ) -> Self {
+        Self {
+            mode,
+            selected: Arc::new(Mutex::new(selected)),
+            on_change,
+        }
+    }
+
+    pub(crate) fn is_selected(&self, id: &str) -> bool {
+        self.selected
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|selected| selected == id)
+    }
+
+    /// Whether the items with `ids` show a checkmark, `None` for items without an id, which keep
+    /// their state.
+    pub(crate) fn checkmarks(&self, ids: &[Option<String>]) -> Vec<Option<bool>> {
+        ids.iter()
+            .map(|id| id.as_deref().map(|id| self.is_selected(id)))
+            .collect()
+    }
+
+    /// Updates the selection after the item `id` was chosen and reports the new selection.
+    pub(crate) fn choose(&self, id: &str) {
+        let selected = {
+            let mut selected = self.selected.lock().unwrap();
+            choose(self.mode, &mut selected, id);
+            selected.clone()
+        };
+
+        (self.on_change)(&selected);
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Palette {
+    /// Shows the selection as the state of the items of `menu`.
+    pub(crate) fn apply(&self, menu: &NSMenu) {
+        unsafe {
+            let items = menu.itemArray();
+            let ids: Vec<Option<String>> = items
+                .iter()
+                .map(|item| {
+                    let identifier: Option<&NSString> = msg_send![item, identifier];
+                    identifier.map(NSString::to_string)
+                })
+                .collect();
+
+            for (item, checked) in items.iter().zip(self.checkmarks(&ids)) {
+                match checked {
+                    Some(true) => item.setState(NSControlStateValueOn),
+                    Some(false) => item.setState(NSControlStateValueOff),
+                    None => {}
+                }
+            }
+        }
+    }
+}
+
+fn choose(mode: SelectionMode, selected: &mut Vec<String>, id: &str) {
+    match mode {
+        SelectionMode::One => {
+            selected.clear();
+            selected.push(id.to_string());
+        }
+        SelectionMode::Any => {
+            if let Some(index) = selected.iter().position(|selected| selected == id) {
+                selected.remove(index);
+            } else {
+                selected.push(id.to_string());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn one_selects_only_the_chosen_item() {
+        let mut selected = ids(&["red"]);
+
+        choose(SelectionMode::One, &mut selected, "blue");
+        assert_eq!(selected, ids(&["blue"]));
+
+        choose(SelectionMode::One, &mut selected, "blue");
+        assert_eq!(selected, ids(&["blue"]));
+    }
+
+    #[test]
+    fn any_toggles_the_chosen_item() {
+        let mut selected = ids(&["red"]);
+
+        choose(SelectionMode::Any, &mut selected, "blue");
+        assert_eq!(selected, ids(&["red", "blue"]));
+
+        choose(SelectionMode::Any, &mut selected, "red");
+        assert_eq!(selected, ids(&["blue"]));
+    }
+
+    #[test]
+    fn choose_reports_the_new_selection() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let palette = {
+            let reported = reported.clone();
+            Palette::new(
+                SelectionMode::Any,
+                ids(&["red"]),
+                Arc::new(move |selected| *reported.lock().unwrap() = selected.to_vec()),
+            )
+        };
+
+        palette.choose("green");
+        assert_eq!(*reported.lock().unwrap(), ids(&["red", "green"]));
+        assert!(palette.is_selected("green"));
+        assert!(!palette.is_selected("blue"));
+    }
+
+    #[test]
+    fn checkmarks_show_the_selection() {
+        let palette = Palette::new(SelectionMode::Any, ids(&["red", "blue"]), Arc::new(|_| {}));
+        let items = [
+            Some(String::from("red")),
+            None,
+            Some(String::from("green")),
+            Some(String::from("blue")),
+        ];
+
+        assert_eq!(
+            palette.checkmarks(&items),
+            [Some(true), None, Some(false), Some(true)]
+        );
+
+        palette.choose("red");
+        assert_eq!(palette.checkmarks(&items[..1]), [Some(false)]);
+    }
+
+    #[test]
+    fn clones_share_the_selection() {
+        let palette = Palette::new(SelectionMode::One, Vec::new(), Arc::new(|_| {}));
+        palette.clone().choose("red");
+
+        assert!(palette.is_selected("red"));
+    }
+}
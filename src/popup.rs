@@ -0,0 +1,101 @@
+use icrate::{
+    AppKit::{NSEvent, NSView},
+    Foundation::{CGPoint, NSInteger},
+};
+use objc2::rc::autoreleasepool;
+use raw_window_handle::{HasRawWindowHandle, RawWindowHandle};
+
+use crate::Menu;
+
+// ----------------------------------------------------------------------------
+
+/// Where and how to show a popup `Menu`, see `Menu::popup(..)`.
+#[derive(Clone, Copy)]
+pub struct Popup<'a> {
+    pub(crate) position: [f64; 2],
+    pub(crate) view: Option<&'a NSView>,
+    pub(crate) positioning_item: Option<usize>,
+}
+
+impl<'a> Popup<'a> {
+    /// Popup at the given position in screen coordinates.
+    ///
+    /// The origin is the bottom left corner of the main screen, so positions on a secondary
+    /// display can be negative.
+    pub fn at(position: [f64; 2]) -> Self {
+        Self {
+            position,
+            view: None,
+            positioning_item: None,
+        }
+    }
+
+    /// Popup at the current mouse position.
+    pub fn at_mouse() -> Self {
+        let pos = unsafe { NSEvent::mouseLocation() };
+        Self::at([pos.x, pos.y])
+    }
+
+    /// The position becomes relative to `view`, in the view's own coordinate system.
+    pub fn in_view(self, view: &'a NSView) -> Self {
+        Self {
+            view: Some(view),
+            ..self
+        }
+    }
+
+    /// The position becomes relative to the content view of `window`.
+    ///
+    /// Returns `None` if `window` is not an AppKit window or has no content view.
+    pub fn in_window<W: HasRawWindowHandle>(self, window: &'a W) -> Option<Self> {
+        let RawWindowHandle::AppKit(handle) = window.raw_window_handle() else {
+            return None;
+        };
+
+        let view = unsafe { (handle.ns_view as *const NSView).as_ref()? };
+        Some(self.in_view(view))
+    }
+
+    /// Index of the item that will be positioned at `position`, e.g. the currently selected
+    /// item of a pop-up button.
+    pub fn positioning_item(self, index: Option<usize>) -> Self {
+        Self {
+            positioning_item: index,
+            ..self
+        }
+    }
+
+    pub(crate) fn point(&self) -> CGPoint {
+        CGPoint::new(self.position[0], self.position[1])
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shows popup menus, abstracted so the selection logic doesn't depend on AppKit.
+pub(crate) trait PopupBackend {
+    /// Shows `menu` and blocks until it is closed. Returns `true` if an item was chosen, after
+    /// performing its action.
+    fn show(&mut self, menu: &Menu, popup: &Popup) -> bool;
+}
+
+pub(crate) struct AppKitPopup;
+
+impl PopupBackend for AppKitPopup {
+    fn show(&mut self, menu: &Menu, popup: &Popup) -> bool {
+        unsafe {
+            autoreleasepool(|_| {
+                let menu = menu.to_objc();
+                let item = popup
+                    .positioning_item
+                    .and_then(|index| menu.itemAtIndex(index as NSInteger));
+
+                menu.popUpMenuPositioningItem_atLocation_inView(
+                    item.as_deref(),
+                    popup.point(),
+                    popup.view,
+                )
+            })
+        }
+    }
+}
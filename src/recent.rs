@@ -0,0 +1,326 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+use serde::{Deserialize, Serialize};
+
+#[cfg(target_os = "macos")]
+use crate::{Action, LazyPolicy, Menu, MenuItem};
+
+// ----------------------------------------------------------------------------
+
+/// An "Open Recent" menu: a most-recently-used list of paths, optionally persisted to a JSON file.
+///
+/// Clones share the same list, so one clone can be kept by the app to call `add(..)` while another
+/// is used for the menu.
+#[derive(Clone)]
+pub struct RecentItemsMenu {
+    list: Arc<Mutex<RecentList>>,
+}
+
+impl RecentItemsMenu {
+    /// An empty list that keeps at most `max_items` paths and isn't persisted.
+    pub fn new(max_items: usize) -> Self {
+        Self {
+            list: Arc::new(Mutex::new(RecentList {
+                paths: Vec::new(),
+                max_items,
+                file: None,
+            })),
+        }
+    }
+
+    /// A list persisted to `file`, loaded from it if it exists.
+    ///
+    /// Every change to the list is written back to `file`.
+    pub fn with_file(max_items: usize, file: impl Into<PathBuf>) -> io::Result<Self> {
+        let file = file.into();
+        let mut paths = match fs::read(&file) {
+            Ok(bytes) => {
+                let recent: RecentFile = serde_json::from_slice(&bytes)?;
+                recent.paths
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(err) => return Err(err),
+        };
+        dedup(&mut paths);
+        paths.truncate(max_items);
+
+        Ok(Self {
+            list: Arc::new(Mutex::new(RecentList {
+                paths,
+                max_items,
+                file: Some(file),
+            })),
+        })
+    }
+
+    /// The paths, most recent first.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        self.list.lock().unwrap().paths.clone()
+    }
+
+    /// Moves `path` to the top of the list, dropping the oldest path if the list is full.
+    pub fn add(&self, path: impl Into<PathBuf>) -> io::Result<()> {
+        let path = path.into();
+        self.update(|paths, max_items| {
+            paths.retain(|other| *other != path);
+            paths.insert(0, path);
+            paths.truncate(max_items);
+        })
+    }
+
+    pub fn remove(&self, path: &Path) -> io::Result<()> {
+        self.update(|paths, _| paths.retain(|other| other != path))
+    }
+
+    pub fn clear(&self) -> io::Result<()> {
+        self.update(|paths, _| paths.clear())
+    }
+
+    /// Removes paths that no longer exist, returns how many were removed.
+    pub fn prune_missing(&self) -> io::Result<usize> {
+        let mut removed = 0;
+        self.update(|paths, _| {
+            let len = paths.len();
+            paths.retain(|path| path.exists());
+            removed = len - paths.len();
+        })?;
+
+        Ok(removed)
+    }
+
+    fn update<F>(&self, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut Vec<PathBuf>, usize),
+    {
+        let mut list = self.list.lock().unwrap();
+        let before = list.paths.clone();
+        let max_items = list.max_items;
+        f(&mut list.paths, max_items);
+
+        match list.file {
+            Some(ref file) if list.paths != before => {
+                let recent = RecentFile {
+                    paths: list.paths.clone(),
+                };
+                fs::write(file, serde_json::to_vec_pretty(&recent)?)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl RecentItemsMenu {
+    /// A menu listing the paths, rebuilt every time it opens. Missing files are pruned first.
+    ///
+    /// `on_open` is called with the chosen path. The items represent their path, see
+    /// `MenuItem::represented(..)`.
+    pub fn menu<F>(&self, title: &str, on_open: F) -> Menu
+    where
+        F: Fn(&Path) + Send + Sync + 'static,
+    {
+        let recent = self.clone();
+        let on_open: Arc<dyn Fn(&Path) + Send + Sync> = Arc::new(on_open);
+        let items = self.menu_items(on_open.clone());
+
+        Menu::new(title, items).on_needs_update(move || Some(recent.menu_items(on_open.clone())))
+    }
+
+    /// Same as `menu(..)` as a submenu item, the paths aren't listed before it first opens.
+    pub fn menu_item<F>(&self, title: &str, on_open: F) -> MenuItem
+    where
+        F: Fn(&Path) + Send + Sync + 'static,
+    {
+        let recent = self.clone();
+        let on_open = Arc::new(on_open);
+
+        MenuItem::lazy_sub_menu(title.to_string(), LazyPolicy::Always, move || {
+            recent.menu_items(on_open.clone())
+        })
+    }
+
+    fn menu_items(&self, on_open: Arc<dyn Fn(&Path) + Send + Sync>) -> Vec<MenuItem> {
+        let _ = self.prune_missing();
+        let paths = self.paths();
+
+        let mut items: Vec<_> = paths
+            .iter()
+            .zip(display_names(&paths))
+            .map(|(path, name)| {
+                let on_open = on_open.clone();
+                let action = Action::callback_with_context(move |context| {
+                    if let Some(path) = context.represented::<PathBuf>() {
+                        on_open(path);
+                    }
+                });
+
+                MenuItem::button(name, action, None)
+                    .tooltip(Some(path.display().to_string()))
+                    .represented(path.clone())
+            })
+            .collect();
+
+        if !items.is_empty() {
+            items.push(MenuItem::SEPARATOR);
+        }
+
+        let recent = self.clone();
+        let clear = Action::callback(move || {
+            let _ = recent.clear();
+        });
+        items.push(
+            MenuItem::button("Clear Menu".to_string(), clear, None).enabled(!paths.is_empty()),
+        );
+
+        items
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+struct RecentList {
+    paths: Vec<PathBuf>,
+    max_items: usize,
+    file: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RecentFile {
+    paths: Vec<PathBuf>,
+}
+
+/// Removes later duplicates, keeping the most recent entry.
+fn dedup(paths: &mut Vec<PathBuf>) {
+    let mut seen = Vec::new();
+    paths.retain(|path| {
+        if seen.contains(path) {
+            false
+        } else {
+            seen.push(path.clone());
+            true
+        }
+    });
+}
+
+/// File names of `paths`, followed by the parent directory's name for file names that appear more
+/// than once, e.g. "notes.txt — work".
+#[cfg(target_os = "macos")]
+fn display_names(paths: &[PathBuf]) -> Vec<String> {
+    let file_name = |path: &PathBuf| {
+        path.file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.display().to_string())
+    };
+
+    paths
+        .iter()
+        .map(|path| {
+            let name = file_name(path);
+            let duplicate = paths
+                .iter()
+                .any(|other| other != path && file_name(other) == name);
+
+            match path.parent().and_then(Path::file_name) {
+                Some(parent) if duplicate => format!("{} — {}", name, parent.to_string_lossy()),
+                _ => name,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn add_moves_path_to_top() {
+        let recent = RecentItemsMenu::new(5);
+        recent.add("/a").unwrap();
+        recent.add("/b").unwrap();
+        recent.add("/a").unwrap();
+
+        assert_eq!(recent.paths(), [PathBuf::from("/a"), PathBuf::from("/b")]);
+    }
+
+    #[test]
+    fn add_drops_oldest_path_when_full() {
+        let recent = RecentItemsMenu::new(2);
+        recent.add("/a").unwrap();
+        recent.add("/b").unwrap();
+        recent.add("/c").unwrap();
+
+        assert_eq!(recent.paths(), [PathBuf::from("/c"), PathBuf::from("/b")]);
+    }
+
+    #[test]
+    fn remove_and_clear() {
+        let recent = RecentItemsMenu::new(5);
+        recent.add("/a").unwrap();
+        recent.add("/b").unwrap();
+
+        recent.remove(Path::new("/a")).unwrap();
+        assert_eq!(recent.paths(), [PathBuf::from("/b")]);
+
+        recent.clear().unwrap();
+        assert!(recent.paths().is_empty());
+    }
+
+    #[test]
+    fn prune_missing_removes_missing_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let existing = dir.path().join("existing.txt");
+        fs::write(&existing, "").unwrap();
+
+        let recent = RecentItemsMenu::new(5);
+        recent.add(dir.path().join("missing.txt")).unwrap();
+        recent.add(&existing).unwrap();
+
+        assert_eq!(recent.prune_missing().unwrap(), 1);
+        assert_eq!(recent.paths(), [existing]);
+    }
+
+    #[test]
+    fn clones_share_the_list() {
+        let recent = RecentItemsMenu::new(5);
+        recent.clone().add("/a").unwrap();
+
+        assert_eq!(recent.paths(), [PathBuf::from("/a")]);
+    }
+
+    #[test]
+    fn file_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("recent.json");
+
+        let recent = RecentItemsMenu::with_file(5, &file).unwrap();
+        assert!(recent.paths().is_empty());
+        recent.add("/a").unwrap();
+        recent.add("/b").unwrap();
+
+        let loaded = RecentItemsMenu::with_file(5, &file).unwrap();
+        assert_eq!(loaded.paths(), [PathBuf::from("/b"), PathBuf::from("/a")]);
+    }
+
+    #[test]
+    fn loading_applies_cap_and_dedup() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("recent.json");
+        fs::write(&file, r#"{ "paths": ["/a", "/b", "/a", "/c"] }"#).unwrap();
+
+        let recent = RecentItemsMenu::with_file(2, &file).unwrap();
+        assert_eq!(recent.paths(), [PathBuf::from("/a"), PathBuf::from("/b")]);
+    }
+
+    #[test]
+    fn loading_invalid_file_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("recent.json");
+        fs::write(&file, "not json").unwrap();
+
+        assert!(RecentItemsMenu::with_file(5, &file).is_err());
+    }
+}
@@ -0,0 +1,214 @@
+use crate::conflicts::reserved_for;
+use crate::NSUInteger;
+use crate::{Key, Modifiers, Shortcut};
+
+// ----------------------------------------------------------------------------
+
+/// The state of a "record shortcut" field, e.g. in a preferences window.
+///
+/// The field feeds its key events in, see `key_down(..)` and `flags_changed(..)`, and shows
+/// `modifiers()` while recording.
+#[derive(Debug, Clone, Default)]
+pub struct ShortcutRecorder {
+    recording: bool,
+    modifiers: Modifiers,
+    shortcut: Option<Shortcut>,
+}
+
+/// The result of a key event, see `ShortcutRecorder::key_down(..)`.
+#[derive(Debug, Clone)]
+pub enum RecorderEvent {
+    /// The event doesn't change the recorded shortcut, e.g. a lone modifier.
+    Ignored,
+    Recorded(Shortcut),
+    /// Delete or Backspace without modifiers removed the shortcut.
+    Cleared,
+    /// Escape without modifiers stopped recording, keeping the previous shortcut.
+    Cancelled,
+    /// The shortcut is reserved by the system, recording continues.
+    Rejected {
+        shortcut: Shortcut,
+        reserved_for: &'static str,
+    },
+}
+
+impl ShortcutRecorder {
+    /// A recorder for a field showing `shortcut`.
+    pub fn new(shortcut: Option<Shortcut>) -> Self {
+        Self {
+            shortcut,
+            ..Self::default()
+        }
+    }
+
+    pub fn shortcut(&self) -> Option<&Shortcut> {
+        self.shortcut.as_ref()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recording
+    }
+
+    /// The modifiers held while recording, to show e.g. "⌥⌘…" before the key is pressed.
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn start(&mut self) {
+        self.recording = true;
+        self.modifiers = Modifiers::new();
+    }
+
+    pub fn stop(&mut self) {
+        self.recording = false;
+        self.modifiers = Modifiers::new();
+    }
+
+    /// Handles a `flagsChanged:` event with `modifierFlags` `mask`. Caps lock is ignored.
+    pub fn flags_changed(&mut self, mask: NSUInteger) {
+        if self.recording {
+            self.modifiers = Modifiers::from_mask(mask).capslock(false);
+        }
+    }
+
+    /// Handles a `keyDown:` event, see `Shortcut::from_mask_and_chars(..)`.
+    pub fn key_down(&mut self, mask: NSUInteger, chars: &str) -> RecorderEvent {
+        if !self.recording {
+            return RecorderEvent::Ignored;
+        }
+
+        let Some(shortcut) = Shortcut::from_mask_and_chars(mask, chars) else {
+            return RecorderEvent::Ignored;
+        };
+
+        if shortcut.modifiers == Modifiers::new() {
+            match shortcut.key {
+                Key::Other(ref key) if key == "\u{1b}" => {
+                    self.stop();
+                    return RecorderEvent::Cancelled;
+                }
+                Key::Delete | Key::Backspace => {
+                    self.stop();
+                    self.shortcut = None;
+                    return RecorderEvent::Cleared;
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(reserved_for) = reserved_for(&shortcut) {
+            return RecorderEvent::Rejected {
+                shortcut,
+                reserved_for,
+            };
+        }
+
+        self.stop();
+        self.shortcut = Some(shortcut.clone());
+        RecorderEvent::Recorded(shortcut)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CAPSLOCK: NSUInteger = 0x10000;
+    const SHIFT: NSUInteger = 0x20000;
+    const COMMAND: NSUInteger = 0x100000;
+
+    fn recording(shortcut: Option<Shortcut>) -> ShortcutRecorder {
+        let mut recorder = ShortcutRecorder::new(shortcut);
+        recorder.start();
+        recorder
+    }
+
+    #[test]
+    fn records_shortcut() {
+        let mut recorder = recording(None);
+        recorder.flags_changed(SHIFT | COMMAND);
+        assert_eq!(
+            recorder.modifiers(),
+            Modifiers::new().shift(true).command(true)
+        );
+
+        let event = recorder.key_down(SHIFT | COMMAND, "E");
+        let expected = Shortcut::new(Key::E).shift(true).command(true);
+        assert!(matches!(event, RecorderEvent::Recorded(ref shortcut) if *shortcut == expected));
+        assert_eq!(recorder.shortcut(), Some(&expected));
+        assert!(!recorder.is_recording());
+    }
+
+    #[test]
+    fn ignores_events_when_not_recording() {
+        let mut recorder = ShortcutRecorder::new(None);
+
+        assert!(matches!(
+            recorder.key_down(COMMAND, "e"),
+            RecorderEvent::Ignored
+        ));
+        assert!(recorder.shortcut().is_none());
+    }
+
+    #[test]
+    fn rejects_reserved_shortcut() {
+        let mut recorder = recording(None);
+
+        let event = recorder.key_down(COMMAND, "q");
+        assert!(matches!(
+            event,
+            RecorderEvent::Rejected {
+                reserved_for: "Quit",
+                ..
+            }
+        ));
+        assert!(recorder.is_recording());
+    }
+
+    #[test]
+    fn escape_cancels_and_delete_clears() {
+        let previous = Shortcut::new(Key::E).command(true);
+
+        let mut recorder = recording(Some(previous.clone()));
+        assert!(matches!(
+            recorder.key_down(0, "\u{1b}"),
+            RecorderEvent::Cancelled
+        ));
+        assert_eq!(recorder.shortcut(), Some(&previous));
+
+        let mut recorder = recording(Some(previous));
+        assert!(matches!(
+            recorder.key_down(0, "\x7F"),
+            RecorderEvent::Cleared
+        ));
+        assert!(recorder.shortcut().is_none());
+    }
+
+    #[test]
+    fn escape_and_delete_work_with_capslock() {
+        let mut recorder = recording(None);
+        recorder.flags_changed(CAPSLOCK);
+        assert_eq!(recorder.modifiers(), Modifiers::new());
+
+        assert!(matches!(
+            recorder.key_down(CAPSLOCK, "\u{1b}"),
+            RecorderEvent::Cancelled
+        ));
+
+        let mut recorder = recording(Some(Shortcut::new(Key::E).command(true)));
+        assert!(matches!(
+            recorder.key_down(CAPSLOCK, "\x08"),
+            RecorderEvent::Cleared
+        ));
+    }
+
+    #[test]
+    fn escape_with_modifiers_is_recorded() {
+        let mut recorder = recording(None);
+
+        assert!(matches!(
+            recorder.key_down(COMMAND, "\u{1b}"),
+            RecorderEvent::Recorded(_)
+        ));
+    }
+}
@@ -0,0 +1,235 @@
+#[cfg(target_os = "macos")]
+use icrate::{
+    AppKit::{
+        NSBoldFontMask, NSColor, NSFont, NSFontAttributeName, NSFontManager,
+        NSForegroundColorAttributeName, NSItalicFontMask,
+    },
+    Foundation::{
+        CGFloat, NSAttributedString, NSAttributedStringKey, NSMutableAttributedString,
+        NSMutableDictionary, NSString,
+    },
+};
+#[cfg(target_os = "macos")]
+use objc2::{msg_send, rc::Id, runtime::AnyObject, ClassType};
+
+// ----------------------------------------------------------------------------
+
+/// Styled text made of runs, e.g. for `MenuItem::attributed_title(..)`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RichText {
+    runs: Vec<TextRun>,
+}
+
+impl RichText {
+    pub fn new(runs: Vec<TextRun>) -> Self {
+        Self { runs }
+    }
+
+    pub fn push(mut self, run: TextRun) -> Self {
+        self.runs.push(run);
+        self
+    }
+
+    pub fn runs(&self) -> &[TextRun] {
+        &self.runs
+    }
+
+    /// The text of all runs, without styling.
+    pub fn plain_text(&self) -> String {
+        self.runs.iter().map(|run| run.text.as_str()).collect()
+    }
+}
+
+impl From<&str> for RichText {
+    fn from(text: &str) -> Self {
+        Self::new(vec![TextRun::new(text)])
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// A piece of `RichText` with a single style.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextRun {
+    pub text: String,
+    pub bold: bool,
+    pub italic: bool,
+    pub color: Option<Color>,
+    /// `None` uses the default menu font size.
+    pub font_size: Option<f64>,
+}
+
+impl TextRun {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            bold: false,
+            italic: false,
+            color: None,
+            font_size: None,
+        }
+    }
+
+    pub fn bold(self, bold: bool) -> Self {
+        Self { bold, ..self }
+    }
+
+    pub fn italic(self, italic: bool) -> Self {
+        Self { italic, ..self }
+    }
+
+    pub fn color(self, color: Option<Color>) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn font_size(self, font_size: Option<f64>) -> Self {
+        Self { font_size, ..self }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Text color, the system colors adapt to light and dark mode.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Label,
+    /// Dimmed text.
+    SecondaryLabel,
+    TertiaryLabel,
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+    Gray,
+    Srgb {
+        red: f64,
+        green: f64,
+        blue: f64,
+        alpha: f64,
+    },
+}
+
+// ----------------------------------------------------------------------------
+
+#[cfg(target_os = "macos")]
+impl RichText {
+    pub(crate) fn to_objc(&self) -> Id<NSAttributedString> {
+        unsafe {
+            let mut string = NSMutableAttributedString::new();
+            for run in self.runs.iter() {
+                string.appendAttributedString(&run.to_objc());
+            }
+
+            Id::into_super(string)
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl TextRun {
+    fn to_objc(&self) -> Id<NSAttributedString> {
+        unsafe {
+            let attributes = NSMutableDictionary::<NSAttributedStringKey, AnyObject>::new();
+
+            let mut font = NSFont::menuFontOfSize(self.font_size.unwrap_or(0.0) as CGFloat);
+            let font_manager = NSFontManager::sharedFontManager();
+            if self.bold {
+                font = font_manager.convertFont_toHaveTrait(&font, NSBoldFontMask);
+            }
+            if self.italic {
+                font = font_manager.convertFont_toHaveTrait(&font, NSItalicFontMask);
+            }
+            let _: () = msg_send![&*attributes, setObject: &*font, forKey: NSFontAttributeName];
+
+            if let Some(color) = self.color {
+                let color = color.to_objc();
+                let _: () = msg_send![
+                    &*attributes,
+                    setObject: &*color,
+                    forKey: NSForegroundColorAttributeName
+                ];
+            }
+
+            let text = NSString::from_str(&self.text);
+            NSAttributedString::initWithString_attributes(
+                NSAttributedString::alloc(),
+                &text,
+                Some(&attributes),
+            )
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl Color {
+    pub(crate) fn to_objc(self) -> Id<NSColor> {
+        unsafe {
+            match self {
+                Color::Label => NSColor::labelColor(),
+                Color::SecondaryLabel => NSColor::secondaryLabelColor(),
+                Color::TertiaryLabel => NSColor::tertiaryLabelColor(),
+                Color::Red => NSColor::systemRedColor(),
+                Color::Orange => NSColor::systemOrangeColor(),
+                Color::Yellow => NSColor::systemYellowColor(),
+                Color::Green => NSColor::systemGreenColor(),
+                Color::Blue => NSColor::systemBlueColor(),
+                Color::Purple => NSColor::systemPurpleColor(),
+                Color::Gray => NSColor::systemGrayColor(),
+                Color::Srgb {
+                    red,
+                    green,
+                    blue,
+                    alpha,
+                } => NSColor::colorWithSRGBRed_green_blue_alpha(
+                    red as CGFloat,
+                    green as CGFloat,
+                    blue as CGFloat,
+                    alpha as CGFloat,
+                ),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_text_joins_runs() {
+        let text = RichText::from("Report")
+            .push(TextRun::new(" — draft").color(Some(Color::SecondaryLabel)));
+
+        assert_eq!(text.plain_text(), "Report — draft");
+        assert_eq!(text.runs().len(), 2);
+    }
+
+    #[test]
+    fn runs_keep_their_style() {
+        let text = RichText::new(vec![
+            TextRun::new("Bold").bold(true),
+            TextRun::new("Big").font_size(Some(18.0)),
+        ]);
+
+        assert!(text.runs()[0].bold);
+        assert!(!text.runs()[0].italic);
+        assert_eq!(text.runs()[0].font_size, None);
+        assert_eq!(text.runs()[1].font_size, Some(18.0));
+    }
+
+    #[test]
+    fn from_str_is_one_plain_run() {
+        assert_eq!(
+            RichText::from("Open"),
+            RichText::new(vec![TextRun::new("Open")])
+        );
+    }
+
+    #[test]
+    fn default_is_empty() {
+        assert!(RichText::default().runs().is_empty());
+        assert_eq!(RichText::default().plain_text(), "");
+    }
+}
@@ -1,6 +1,14 @@
 #![allow(dead_code)]
 
-use objc2::ffi::NSUInteger;
+use std::cmp::Ordering;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::str::FromStr;
+
+use crate::conflicts::{display, normalize};
+use crate::layout::{KeyCode, Layout};
+use crate::NSUInteger;
 
 // ----------------------------------------------------------------------------
 
@@ -61,9 +69,42 @@ pub enum Key {
     Other(String),
 }
 
-impl ToString for Key {
-    fn to_string(&self) -> String {
-        match self {
+/// Keys are equal if they type the same character, ignoring case: `Key::Other("a")` and
+/// `Key::Other("A")` are `Key::A`, `Key::Other("\t")` is `Key::Tab`.
+///
+/// Case matters for shortcuts though, an uppercase key is typed with shift. `Shortcut::new(..)`
+/// moves it into the modifiers, so `Shortcut::new(Key::Other("E"))` has shift and
+/// `Shortcut::new(Key::E)` doesn't.
+impl PartialEq for Key {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Key {}
+
+impl Hash for Key {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+impl PartialOrd for Key {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Key {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().cmp(&other.canonical())
+    }
+}
+
+/// The characters the key types, as used for key equivalents.
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let key = match self {
             Key::A => String::from("a"),
             Key::B => String::from("b"),
             Key::C => String::from("c"),
@@ -117,7 +158,9 @@ impl ToString for Key {
             Key::Enter => String::from("\r"),
             Key::Tab => String::from("\t"),
             Key::Other(key) => key.clone(),
-        }
+        };
+
+        f.write_str(&key)
     }
 }
 
@@ -126,23 +169,455 @@ impl ToString for Key {
 #[derive(Debug, Clone)]
 pub struct Shortcut {
     pub(crate) key: Key,
+    pub(crate) modifiers: Modifiers,
+}
+
+impl Shortcut {
+    /// A shortcut on `key` without modifiers, except for shift if `key` is uppercase: AppKit
+    /// types `Key::Other("E")` with shift, so it's kept as `Key::E` with shift.
+    pub fn new(key: Key) -> Self {
+        let (key, shift) = key.unshifted();
+        Self {
+            key,
+            modifiers: Modifiers::new().shift(shift),
+        }
+    }
+    /// The shortcut of a key event, from its `modifierFlags` and `charactersIgnoringModifiers`.
+    ///
+    /// Uppercase letters are decoded as the lowercase key with shift, function keys from their
+    /// private use characters. Caps lock is ignored, as AppKit ignores it when matching key
+    /// equivalents. Returns `None` if `chars` is empty, e.g. for a lone modifier.
+    pub fn from_mask_and_chars(mask: NSUInteger, chars: &str) -> Option<Self> {
+        let mut chars_iter = chars.chars();
+        let (first, rest) = (chars_iter.next()?, chars_iter.next());
+
+        let (key, shifted) = match first {
+            '\u{F704}'..='\u{F70F}' if rest.is_none() => {
+                let keys = [
+                    Key::F1,
+                    Key::F2,
+                    Key::F3,
+                    Key::F4,
+                    Key::F5,
+                    Key::F6,
+                    Key::F7,
+                    Key::F8,
+                    Key::F9,
+                    Key::F10,
+                    Key::F11,
+                    Key::F12,
+                ];
+                (
+                    keys[first as usize - NSF1FUNCTIONKEY as usize].clone(),
+                    false,
+                )
+            }
+            '\x7F' if rest.is_none() => (Key::Delete, false),
+            '\x08' if rest.is_none() => (Key::Backspace, false),
+            '\r' if rest.is_none() => (Key::Enter, false),
+            '\t' if rest.is_none() => (Key::Tab, false),
+            _ => Key::Other(chars.to_string()).unshifted(),
+        };
+
+        let modifiers = Modifiers::from_mask(mask).capslock(false);
+        Some(Self {
+            key,
+            modifiers: modifiers.shift(modifiers.shift || shifted),
+        })
+    }
+
+    /// A shortcut on the physical key `code`, typing whatever character that key has on
+    /// `layout`. E.g. `KeyCode::Z` is ⌘Y on a German layout, so undo stays next to ⌘X.
+    ///
+    /// Returns `None` for unknown codes, see `Key::from_key_code(..)`.
+    pub fn physical(code: KeyCode, layout: Layout) -> Option<Self> {
+        Key::from_key_code(code, layout).map(Self::new)
+    }
+    pub fn capslock(self, capslock: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.capslock(capslock),
+            ..self
+        }
+    }
+    pub fn shift(self, shift: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.shift(shift),
+            ..self
+        }
+    }
+    pub fn control(self, control: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.control(control),
+            ..self
+        }
+    }
+    pub fn option(self, option: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.option(option),
+            ..self
+        }
+    }
+    pub fn command(self, command: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.command(command),
+            ..self
+        }
+    }
+    /// See `Modifiers::primary(..)`.
+    pub fn primary(self, primary: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.primary(primary),
+            ..self
+        }
+    }
+    /// See `Modifiers::alt(..)`.
+    pub fn alt(self, alt: bool) -> Self {
+        Self {
+            modifiers: self.modifiers.alt(alt),
+            ..self
+        }
+    }
+
+    pub(crate) fn mask(&self) -> NSUInteger {
+        self.modifiers.mask()
+    }
+
+    /// The key and modifiers AppKit matches this shortcut with, see `MenuBar::shortcut_conflicts()`.
+    fn canonical(&self) -> (String, NSUInteger) {
+        normalize(&self.key.to_string(), self.mask())
+    }
+
+    /// The shortcut as shown in menus, e.g. "⇧⌘E".
+    pub(crate) fn symbols(&self) -> String {
+        let (key, mask) = self.canonical();
+        display(&key, mask)
+    }
+}
+
+/// Shortcuts are equal if AppKit matches them to the same key press: `Key::Other("E")` is
+/// `Key::E` with shift, caps lock is ignored. Shortcuts with equal keys and modifiers are always
+/// equal, see `Shortcut::new(..)`.
+impl PartialEq for Shortcut {
+    fn eq(&self, other: &Self) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+impl Eq for Shortcut {}
+
+impl Hash for Shortcut {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.canonical().hash(state);
+    }
+}
+
+impl PartialOrd for Shortcut {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Shortcut {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.canonical().cmp(&other.canonical())
+    }
+}
+
+/// Modifier and key names joined by `+`, e.g. "Cmd+Shift+E".
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let modifiers = [
+            (self.modifiers.primary, "CmdOrCtrl"),
+            (self.modifiers.control, "Ctrl"),
+            (self.modifiers.option, "Alt"),
+            (self.modifiers.shift, "Shift"),
+            (self.modifiers.command, "Cmd"),
+            (self.modifiers.capslock, "CapsLock"),
+        ];
+        for (_, name) in modifiers.iter().filter(|(pressed, _)| *pressed) {
+            write!(f, "{}+", name)?;
+        }
+
+        write!(f, "{}", self.key.name())
+    }
+}
+
+/// Parses modifier and key names joined by `+`, case insensitive, e.g. "cmd+shift+e".
+///
+/// Modifiers are `Cmd`/`Command`, `Ctrl`/`Control`, `Alt`/`Opt`/`Option`, `Shift`, `CapsLock`
+/// and `CmdOrCtrl`/`CommandOrControl` for the primary modifier, see `Modifiers::primary(..)`.
+/// Keys are letters, digits, `F1`…`F12`, `Delete`, `Backspace`, `Enter`/`Return`, `Tab`, `Space`,
+/// `Esc` or any other single character.
+impl FromStr for Shortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let error = || ParseShortcutError {
+            input: s.to_string(),
+        };
+
+        // "+" on its own is a key, e.g. "Cmd++"
+        let (modifiers, key) = match s.strip_suffix("++") {
+            Some(modifiers) => (modifiers, "+"),
+            None if s.trim() == "+" => ("", "+"),
+            None => s.rsplit_once('+').unwrap_or(("", s)),
+        };
+        let key = Key::from_name(key.trim()).ok_or_else(error)?;
+
+        let mut shortcut = Shortcut::new(key);
+        for modifier in modifiers.split('+').filter(|modifier| !modifier.is_empty()) {
+            shortcut = match modifier.trim().to_lowercase().as_str() {
+                "cmd" | "command" => shortcut.command(true),
+                "ctrl" | "control" => shortcut.control(true),
+                "alt" | "opt" | "option" => shortcut.option(true),
+                "cmdorctrl" | "commandorcontrol" => shortcut.primary(true),
+                "shift" => shortcut.shift(true),
+                "capslock" => shortcut.capslock(true),
+                _ => return Err(error()),
+            };
+        }
+
+        Ok(shortcut)
+    }
+}
+
+/// A shortcut string that couldn't be parsed, see `Shortcut::from_str(..)`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseShortcutError {
+    input: String,
+}
+
+impl fmt::Display for ParseShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid shortcut '{}'", self.input)
+    }
+}
+
+impl Error for ParseShortcutError {}
+
+impl Key {
+    /// The name used in shortcut strings, see `Shortcut::from_str(..)`.
+    fn name(&self) -> String {
+        match self {
+            Key::F1 => String::from("F1"),
+            Key::F2 => String::from("F2"),
+            Key::F3 => String::from("F3"),
+            Key::F4 => String::from("F4"),
+            Key::F5 => String::from("F5"),
+            Key::F6 => String::from("F6"),
+            Key::F7 => String::from("F7"),
+            Key::F8 => String::from("F8"),
+            Key::F9 => String::from("F9"),
+            Key::F10 => String::from("F10"),
+            Key::F11 => String::from("F11"),
+            Key::F12 => String::from("F12"),
+            Key::Delete => String::from("Delete"),
+            Key::Backspace => String::from("Backspace"),
+            Key::Enter => String::from("Enter"),
+            Key::Tab => String::from("Tab"),
+            Key::Other(key) if key == " " => String::from("Space"),
+            Key::Other(key) if key == "\u{1b}" => String::from("Esc"),
+            Key::Other(key) => key.clone(),
+            key => key.to_string().to_uppercase(),
+        }
+    }
+
+    fn canonical(&self) -> String {
+        self.to_string().to_lowercase()
+    }
+
+    /// The key typed without shift and whether it's typed with shift, e.g. `Key::E` and `true`
+    /// for `Key::Other("E")`.
+    fn unshifted(self) -> (Key, bool) {
+        match self {
+            Key::Other(key) => {
+                let lowercase = key.to_lowercase();
+                let shift = lowercase != key;
+                let known = Key::from_name(&lowercase).filter(|_| lowercase.chars().count() == 1);
+
+                (known.unwrap_or(Key::Other(lowercase)), shift)
+            }
+            key => (key, false),
+        }
+    }
+
+    /// The key typed by the physical key `code` on `layout`, `None` for unknown codes.
+    pub fn from_key_code(code: KeyCode, layout: Layout) -> Option<Key> {
+        let key = match code {
+            KeyCode::RETURN => Key::Enter,
+            KeyCode::TAB => Key::Tab,
+            KeyCode::SPACE => Key::Other(String::from(" ")),
+            KeyCode::DELETE => Key::Delete,
+            KeyCode::ESCAPE => Key::Other(String::from("\u{1b}")),
+            KeyCode::F1 => Key::F1,
+            KeyCode::F2 => Key::F2,
+            KeyCode::F3 => Key::F3,
+            KeyCode::F4 => Key::F4,
+            KeyCode::F5 => Key::F5,
+            KeyCode::F6 => Key::F6,
+            KeyCode::F7 => Key::F7,
+            KeyCode::F8 => Key::F8,
+            KeyCode::F9 => Key::F9,
+            KeyCode::F10 => Key::F10,
+            KeyCode::F11 => Key::F11,
+            KeyCode::F12 => Key::F12,
+            code => Key::from_name(&layout.character(code)?.to_string())?,
+        };
+
+        Some(key)
+    }
+
+    fn from_name(name: &str) -> Option<Key> {
+        let key = match name.to_lowercase().as_str() {
+            "a" => Key::A,
+            "b" => Key::B,
+            "c" => Key::C,
+            "d" => Key::D,
+            "e" => Key::E,
+            "f" => Key::F,
+            "g" => Key::G,
+            "h" => Key::H,
+            "i" => Key::I,
+            "j" => Key::J,
+            "k" => Key::K,
+            "l" => Key::L,
+            "m" => Key::M,
+            "n" => Key::N,
+            "o" => Key::O,
+            "p" => Key::P,
+            "q" => Key::Q,
+            "r" => Key::R,
+            "s" => Key::S,
+            "t" => Key::T,
+            "u" => Key::U,
+            "v" => Key::V,
+            "w" => Key::W,
+            "x" => Key::X,
+            "y" => Key::Y,
+            "z" => Key::Z,
+            "1" => Key::Num1,
+            "2" => Key::Num2,
+            "3" => Key::Num3,
+            "4" => Key::Num4,
+            "5" => Key::Num5,
+            "6" => Key::Num6,
+            "7" => Key::Num7,
+            "8" => Key::Num8,
+            "9" => Key::Num9,
+            "0" => Key::Num0,
+            "f1" => Key::F1,
+            "f2" => Key::F2,
+            "f3" => Key::F3,
+            "f4" => Key::F4,
+            "f5" => Key::F5,
+            "f6" => Key::F6,
+            "f7" => Key::F7,
+            "f8" => Key::F8,
+            "f9" => Key::F9,
+            "f10" => Key::F10,
+            "f11" => Key::F11,
+            "f12" => Key::F12,
+            "delete" => Key::Delete,
+            "backspace" => Key::Backspace,
+            "enter" | "return" => Key::Enter,
+            "tab" => Key::Tab,
+            "space" => Key::Other(String::from(" ")),
+            "esc" | "escape" => Key::Other(String::from("\u{1b}")),
+            _ if name.chars().count() == 1 => Key::Other(name.to_string()),
+            _ => return None,
+        };
+
+        Some(key)
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// Shortcuts pressed one after another, e.g. ⌘K ⌘C.
+///
+/// AppKit can't use chords as key equivalents, see `ChordMatcher` for dispatching them and
+/// `MenuItem::chord(..)` for showing them in menus.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ChordShortcut {
+    keys: Vec<Shortcut>,
+}
+
+impl ChordShortcut {
+    pub fn new(first: Shortcut) -> Self {
+        Self { keys: vec![first] }
+    }
+
+    pub fn then(mut self, next: Shortcut) -> Self {
+        self.keys.push(next);
+        self
+    }
+
+    pub fn keys(&self) -> &[Shortcut] {
+        &self.keys
+    }
+
+    /// Whether `keys` are the first keys of this chord, or all of them.
+    pub(crate) fn starts_with(&self, keys: &[Shortcut]) -> bool {
+        keys.len() <= self.keys.len() && self.keys.iter().zip(keys).all(|(key, other)| key == other)
+    }
+
+    /// The chord as shown in menus, e.g. "⌘K ⌘C".
+    pub(crate) fn symbols(&self) -> String {
+        let symbols: Vec<_> = self.keys.iter().map(Shortcut::symbols).collect();
+        symbols.join(" ")
+    }
+}
+
+/// Shortcuts separated by spaces, e.g. "Cmd+K Cmd+C".
+impl fmt::Display for ChordShortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let keys: Vec<_> = self.keys.iter().map(Shortcut::to_string).collect();
+        write!(f, "{}", keys.join(" "))
+    }
+}
 
+/// Parses shortcuts separated by spaces, see `Shortcut::from_str(..)`.
+impl FromStr for ChordShortcut {
+    type Err = ParseShortcutError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let keys = s
+            .split_whitespace()
+            .map(Shortcut::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if keys.is_empty() {
+            return Err(ParseShortcutError {
+                input: s.to_string(),
+            });
+        }
+
+        Ok(Self { keys })
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+/// The modifier keys of a `Shortcut`, also used on their own e.g. for alternate items.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Modifiers {
     capslock: bool,
     shift: bool,
     control: bool,
     option: bool,
     command: bool,
+    primary: bool,
 }
 
-impl Shortcut {
-    pub fn new(key: Key) -> Self {
+impl Modifiers {
+    pub const fn new() -> Self {
         Self {
-            key,
             capslock: false,
             shift: false,
             control: false,
             option: false,
             command: false,
+            primary: false,
         }
     }
     pub fn capslock(self, capslock: bool) -> Self {
@@ -160,6 +635,30 @@ impl Shortcut {
     pub fn command(self, command: bool) -> Self {
         Self { command, ..self }
     }
+    /// The platform's primary modifier: command on macOS, control elsewhere. Lets menus shared
+    /// with Windows and Linux builds use one definition, e.g. `primary(true)` for ⌘S and Ctrl+S.
+    ///
+    /// Kept apart from `command(..)` and `control(..)`, so it's written back as "CmdOrCtrl" and
+    /// `primary(false)` doesn't clear an explicit command or control.
+    pub fn primary(self, primary: bool) -> Self {
+        Self { primary, ..self }
+    }
+    /// Alt, which is option on macOS.
+    pub fn alt(self, alt: bool) -> Self {
+        self.option(alt)
+    }
+
+    /// The modifiers of a `NSEventModifierFlags` mask, other flags are ignored.
+    pub fn from_mask(mask: NSUInteger) -> Self {
+        Self {
+            capslock: mask & (1 << 16) != 0,
+            shift: mask & (1 << 17) != 0,
+            control: mask & (1 << 18) != 0,
+            option: mask & (1 << 19) != 0,
+            command: mask & (1 << 20) != 0,
+            primary: false,
+        }
+    }
 
     pub(crate) fn mask(&self) -> NSUInteger {
         let mut mask = 0;
@@ -179,6 +678,13 @@ impl Shortcut {
         if self.command {
             mask |= 1 << 20;
         }
+        if self.primary {
+            mask |= if cfg!(target_os = "macos") {
+                1 << 20
+            } else {
+                1 << 18
+            };
+        }
 
         mask
     }
@@ -258,3 +764,236 @@ const NSREDOFUNCTIONKEY: u16 = 0xF744;
 const NSFINDFUNCTIONKEY: u16 = 0xF745;
 const NSHELPFUNCTIONKEY: u16 = 0xF746;
 const NSMODESWITCHFUNCTIONKEY: u16 = 0xF74;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::hash_map::DefaultHasher;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    const CAPSLOCK: NSUInteger = 0x10000;
+    const SHIFT: NSUInteger = 0x20000;
+    const CONTROL: NSUInteger = 0x40000;
+    const OPTION: NSUInteger = 0x80000;
+    const COMMAND: NSUInteger = 0x100000;
+
+    #[test]
+    fn modifier_masks() {
+        assert_eq!(Modifiers::new().mask(), 0);
+        assert_eq!(Modifiers::new().command(true).mask(), COMMAND);
+        assert_eq!(Modifiers::new().shift(true).command(true).mask(), 0x120000);
+        assert_eq!(Modifiers::new().control(true).option(true).mask(), 0xC0000);
+        assert_eq!(Modifiers::new().capslock(true).mask(), CAPSLOCK);
+    }
+
+    #[test]
+    fn primary_is_resolved_in_mask() {
+        let primary = if cfg!(target_os = "macos") {
+            COMMAND
+        } else {
+            CONTROL
+        };
+
+        assert_eq!(Modifiers::new().primary(true).mask(), primary);
+        assert_eq!(
+            Modifiers::new().primary(true).command(true).mask(),
+            primary | COMMAND
+        );
+    }
+
+    #[test]
+    fn primary_false_keeps_explicit_modifiers() {
+        let modifiers = Modifiers::new().command(true).control(true).primary(false);
+        assert_eq!(modifiers.mask(), CONTROL | COMMAND);
+    }
+
+    #[test]
+    fn primary_round_trips_as_cmd_or_ctrl() {
+        let shortcut = Shortcut::new(Key::S).primary(true).shift(true);
+        assert_eq!(shortcut.to_string(), "CmdOrCtrl+Shift+S");
+
+        let parsed: Shortcut = "CmdOrCtrl+Shift+S".parse().unwrap();
+        assert_eq!(parsed.modifiers, shortcut.modifiers);
+        assert_eq!(parsed.to_string(), "CmdOrCtrl+Shift+S");
+    }
+
+    #[test]
+    fn physical_follows_layout() {
+        let undo = |layout| {
+            Shortcut::physical(KeyCode::Z, layout)
+                .unwrap()
+                .command(true)
+        };
+
+        assert_eq!(undo(Layout::Us), Shortcut::new(Key::Z).command(true));
+        assert_eq!(undo(Layout::German), Shortcut::new(Key::Y).command(true));
+        assert_eq!(undo(Layout::French), Shortcut::new(Key::W).command(true));
+        assert_eq!(
+            Shortcut::physical(KeyCode::F5, Layout::Dvorak).unwrap(),
+            Shortcut::new(Key::F5)
+        );
+    }
+
+    #[test]
+    fn physical_rejects_unknown_codes() {
+        assert!(Shortcut::physical(KeyCode(0x34), Layout::Us).is_none());
+    }
+
+    #[test]
+    fn from_mask_inverts_mask() {
+        for mask in [0, SHIFT, CONTROL | OPTION, SHIFT | COMMAND, 0x1F0000] {
+            assert_eq!(Modifiers::from_mask(mask).mask(), mask);
+        }
+    }
+
+    #[test]
+    fn from_mask_ignores_other_flags() {
+        // NSEventModifierFlagNumericPad and NSEventModifierFlagFunction
+        let mask = COMMAND | 0x200000 | 0x800000;
+        assert_eq!(Modifiers::from_mask(mask), Modifiers::new().command(true));
+    }
+
+    #[test]
+    fn from_mask_and_chars_decodes_event() {
+        let shortcut = Shortcut::from_mask_and_chars(COMMAND, "s").unwrap();
+        assert_eq!(shortcut.key, Key::S);
+        assert_eq!(shortcut.mask(), COMMAND);
+
+        let shortcut = Shortcut::from_mask_and_chars(COMMAND, "S").unwrap();
+        assert_eq!(shortcut.key, Key::S);
+        assert_eq!(shortcut.mask(), SHIFT | COMMAND);
+
+        let shortcut = Shortcut::from_mask_and_chars(OPTION, "\u{F705}").unwrap();
+        assert_eq!(shortcut.key, Key::F2);
+        assert_eq!(shortcut.mask(), OPTION);
+
+        assert!(Shortcut::from_mask_and_chars(SHIFT, "").is_none());
+    }
+
+    #[test]
+    fn from_mask_and_chars_ignores_capslock() {
+        let shortcut = Shortcut::from_mask_and_chars(CAPSLOCK | COMMAND, "s").unwrap();
+        assert_eq!(shortcut.modifiers, Modifiers::new().command(true));
+
+        let shortcut = Shortcut::from_mask_and_chars(CAPSLOCK, "\u{1b}").unwrap();
+        assert_eq!(shortcut.modifiers, Modifiers::new());
+    }
+
+    #[test]
+    fn uppercase_key_implies_shift() {
+        let other = Shortcut::new(Key::Other(String::from("E"))).command(true);
+
+        assert_eq!(other.key, Key::E);
+        assert_eq!(other.modifiers, Modifiers::new().shift(true).command(true));
+        assert_eq!(other, Shortcut::new(Key::E).shift(true).command(true));
+        assert_ne!(other, Shortcut::new(Key::E).command(true));
+        assert_eq!(other.to_string(), "Shift+Cmd+E");
+    }
+
+    fn hash<T: Hash>(value: &T) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        value.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn key() -> impl Strategy<Value = Key> {
+        prop_oneof![
+            "[a-z0-9]".prop_map(|name| Key::from_name(&name).unwrap()),
+            (1..=12u8).prop_map(|n| Key::from_name(&format!("f{}", n)).unwrap()),
+            Just(Key::Delete),
+            Just(Key::Backspace),
+            Just(Key::Enter),
+            Just(Key::Tab),
+            "[a-zA-Z0-9 ,.\t\r]{1,2}".prop_map(Key::Other),
+        ]
+    }
+
+    /// Two spellings of the same key, e.g. `Key::E` and `Key::Other("E")`.
+    fn equal_keys() -> impl Strategy<Value = (Key, Key)> {
+        ("[a-z0-9]", any::<bool>()).prop_map(|(name, uppercase)| {
+            let other = if uppercase {
+                name.to_uppercase()
+            } else {
+                name.clone()
+            };
+            (Key::from_name(&name).unwrap(), Key::Other(other))
+        })
+    }
+
+    fn modifiers() -> impl Strategy<Value = Modifiers> {
+        any::<[bool; 6]>().prop_map(|[capslock, shift, control, option, command, primary]| {
+            Modifiers {
+                capslock,
+                shift,
+                control,
+                option,
+                command,
+                primary,
+            }
+        })
+    }
+
+    /// `Shortcut::new(key)` with `modifiers` added.
+    fn with_modifiers(key: Key, modifiers: Modifiers) -> Shortcut {
+        let shortcut = Shortcut::new(key);
+        Shortcut {
+            modifiers: modifiers.shift(modifiers.shift || shortcut.modifiers.shift),
+            ..shortcut
+        }
+    }
+
+    fn shortcut() -> impl Strategy<Value = Shortcut> {
+        (key(), modifiers()).prop_map(|(key, modifiers)| with_modifiers(key, modifiers))
+    }
+
+    proptest! {
+        #[test]
+        fn other_letters_and_digits_are_known_keys((key, other) in equal_keys()) {
+            prop_assert_eq!(&key, &other);
+            prop_assert_eq!(hash(&key), hash(&other));
+            prop_assert_eq!(key.cmp(&other), Ordering::Equal);
+        }
+
+        #[test]
+        fn key_eq_hash_and_ord_agree(a in key(), b in key()) {
+            prop_assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            if a == b {
+                prop_assert_eq!(hash(&a), hash(&b));
+            }
+        }
+
+        #[test]
+        fn shortcut_eq_hash_and_ord_agree(a in shortcut(), b in shortcut()) {
+            prop_assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+            prop_assert_eq!(a.cmp(&b), b.cmp(&a).reverse());
+            if a == b {
+                prop_assert_eq!(hash(&a), hash(&b));
+            }
+        }
+
+        #[test]
+        fn equal_keys_and_modifiers_make_equal_shortcuts(
+            (key, other) in equal_keys(),
+            modifiers in modifiers(),
+        ) {
+            let a = with_modifiers(key, modifiers);
+            let b = with_modifiers(other, modifiers);
+
+            prop_assert_eq!(&a.key, &b.key);
+            if a.modifiers == b.modifiers {
+                prop_assert_eq!(&a, &b);
+                prop_assert_eq!(hash(&a), hash(&b));
+            }
+        }
+
+        #[test]
+        fn ord_is_transitive(a in shortcut(), b in shortcut(), c in shortcut()) {
+            if a <= b && b <= c {
+                prop_assert!(a <= c);
+            }
+        }
+    }
+}
@@ -0,0 +1,211 @@
+use std::sync::Arc;
+
+use icrate::{
+    AppKit::{NSStatusBar, NSStatusItem},
+    Foundation::{CGFloat, NSString},
+};
+use objc2::{
+    class,
+    declare::ClassBuilder,
+    msg_send,
+    rc::{autoreleasepool, Id},
+    runtime::{AnyClass, NSObject, Sel},
+    sel,
+};
+use once_cell::sync::Lazy;
+
+use crate::{id, item::load, Action, Image, Menu};
+
+// ----------------------------------------------------------------------------
+
+/// Same as `NSVariableStatusItemLength`.
+const VARIABLE_LENGTH: CGFloat = -1.0;
+
+/// An item in the system status bar (a "menu bar extra").
+///
+/// Clicking the item either shows its `Menu` or fires its `Action`, whichever was set last.
+///
+/// # Info
+/// The item is removed from the status bar when dropped.
+pub struct StatusItem {
+    objc: Id<NSStatusItem>,
+    target: Option<Id<NSObject>>,
+}
+
+impl StatusItem {
+    /// Adds a new, empty item to the system status bar.
+    pub fn new() -> Self {
+        let objc = unsafe {
+            let status_bar = NSStatusBar::systemStatusBar();
+            status_bar.statusItemWithLength(VARIABLE_LENGTH)
+        };
+
+        Self { objc, target: None }
+    }
+
+    pub fn title(self, title: Option<&str>) -> Self {
+        self.set_title(title);
+        self
+    }
+
+    pub fn image(self, image: Option<&Image>) -> Self {
+        self.set_image(image);
+        self
+    }
+
+    pub fn tooltip(self, tooltip: Option<&str>) -> Self {
+        self.set_tooltip(tooltip);
+        self
+    }
+
+    pub fn menu(mut self, menu: &Menu) -> Self {
+        self.set_menu(menu);
+        self
+    }
+
+    pub fn action(mut self, action: Action) -> Self {
+        self.set_action(action);
+        self
+    }
+
+    pub fn set_title(&self, title: Option<&str>) {
+        unsafe {
+            if let Some(button) = self.objc.button() {
+                let title = NSString::from_str(title.unwrap_or(""));
+                button.setTitle(&title);
+            }
+        }
+    }
+
+    pub fn set_image(&self, image: Option<&Image>) {
+        unsafe {
+            if let Some(button) = self.objc.button() {
+                button.setImage(image.map(|image| image.to_objc()).as_deref());
+            }
+        }
+    }
+
+    pub fn set_tooltip(&self, tooltip: Option<&str>) {
+        unsafe {
+            if let Some(button) = self.objc.button() {
+                let tooltip = tooltip.map(NSString::from_str);
+                button.setToolTip(tooltip.as_deref());
+            }
+        }
+    }
+
+    /// Shows `menu` when the item is clicked. Replaces any previously set `Action`.
+    ///
+    /// The menu is copied, so changes made to `menu` afterwards require calling this again.
+    pub fn set_menu(&mut self, menu: &Menu) {
+        unsafe {
+            autoreleasepool(|_| {
+                if let Some(button) = self.objc.button() {
+                    button.setTarget(None);
+                    button.setAction(None);
+                }
+                self.target = None;
+
+                self.objc.setMenu(Some(&menu.to_objc()));
+            });
+        }
+    }
+
+    /// Fires `action` when the item is clicked. Replaces any previously set `Menu`.
+    pub fn set_action(&mut self, action: Action) {
+        unsafe {
+            self.objc.setMenu(None);
+
+            let Some(button) = self.objc.button() else {
+                return;
+            };
+
+            self.target = match action {
+                Action::Callback(ref action) => Some(new_target(action.clone())),
+                Action::ContextCallback(_) => {
+                    let action = action.clone();
+                    Some(new_target(Arc::new(move || action.perform())))
+                }
+                _ => None,
+            };
+
+            let target = self.target.as_deref().map(|target| &**target);
+            button.setTarget(target);
+            button.setAction(Some(action.to_sel()));
+        }
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        unsafe { self.objc.setVisible(visible) }
+    }
+}
+
+impl Default for StatusItem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for StatusItem {
+    fn drop(&mut self) {
+        unsafe {
+            NSStatusBar::systemStatusBar().removeStatusItem(&self.objc);
+        }
+    }
+}
+
+// ----------------------------------------------------------------------------
+
+static BLOCK_PTR: &str = "cacaoStatusItemBlockPtr";
+
+fn new_target(action: Arc<dyn Fn() + 'static>) -> Id<NSObject> {
+    let handler = Box::new(action);
+    let ptr = Box::into_raw(handler);
+
+    unsafe {
+        let objc: id = msg_send![register_status_item_target_class(), new];
+        (&mut *objc).set_ivar(BLOCK_PTR, ptr as usize);
+
+        Id::new(objc).unwrap()
+    }
+}
+
+extern "C" fn dealloc_cacao_status_item_target(this: &NSObject, _: Sel) {
+    unsafe {
+        let ptr: usize = *this.ivar(BLOCK_PTR);
+        let obj = ptr as *mut Arc<dyn Fn() + 'static>;
+
+        if !obj.is_null() {
+            let _handler = Box::from_raw(obj);
+        }
+
+        let _: () = msg_send![super(this, class!(NSObject)), dealloc];
+    }
+}
+
+extern "C" fn fire_block_action(this: &NSObject, _: Sel, _sender: id) {
+    let action = load::<Arc<dyn Fn() + 'static>>(this, BLOCK_PTR);
+    action();
+}
+
+fn register_status_item_target_class() -> &'static AnyClass {
+    static CLASS: Lazy<&'static AnyClass> = Lazy::new(|| unsafe {
+        let mut builder = ClassBuilder::new("CacaoStatusItemTarget", class!(NSObject)).unwrap();
+
+        builder.add_ivar::<usize>(BLOCK_PTR);
+
+        builder.add_method(
+            sel!(dealloc),
+            dealloc_cacao_status_item_target as unsafe extern "C" fn(_, _),
+        );
+
+        builder.add_method(
+            sel!(fireBlockAction:),
+            fire_block_action as unsafe extern "C" fn(_, _, id),
+        );
+
+        builder.register()
+    });
+
+    &CLASS
+}
@@ -0,0 +1,51 @@
+use std::rc::Rc;
+
+use icrate::AppKit::{NSView, NSViewWidthSizable};
+use objc2::rc::Id;
+
+// ----------------------------------------------------------------------------
+
+/// Content of a custom view item, see `MenuItem::custom_view(..)`.
+///
+/// # Event handling
+/// While a menu is open AppKit tracks it in `NSEventTrackingRunLoopMode`:
+/// - Controls in the view (sliders, buttons) receive mouse events as usual, but choosing them
+///   doesn't close the menu. Call `cancelTracking` on `view.enclosingMenuItem().menu()` to close it.
+/// - Timers and animations that update the view must be scheduled in `NSRunLoopCommonModes`,
+///   otherwise they're paused while the menu is open.
+/// - Key events go to the menu, not the view. Text fields won't receive typed text.
+#[derive(Clone)]
+pub enum ItemView {
+    /// The same view is used by every realization of the item, so the item should only be part
+    /// of a single realized menu at a time.
+    Objc(Id<NSView>),
+    /// A new view is built for every realization of the item.
+    Builder(Rc<dyn Fn() -> Id<NSView> + 'static>),
+}
+
+impl ItemView {
+    pub fn from_view(view: Id<NSView>) -> Self {
+        Self::Objc(view)
+    }
+
+    pub fn from_builder<F>(builder: F) -> Self
+    where
+        F: Fn() -> Id<NSView> + 'static,
+    {
+        Self::Builder(Rc::new(builder))
+    }
+
+    /// The view is resized to the width of the menu, its height is kept.
+    pub(crate) fn to_objc(&self) -> Id<NSView> {
+        let view = match self {
+            ItemView::Objc(view) => view.clone(),
+            ItemView::Builder(builder) => builder(),
+        };
+
+        unsafe {
+            view.setAutoresizingMask(NSViewWidthSizable);
+        }
+
+        view
+    }
+}
@@ -0,0 +1,287 @@
+#[cfg(target_os = "macos")]
+use std::sync::Arc;
+
+#[cfg(target_os = "macos")]
+use crate::{Action, Image, Key, MenuItem, MenuItemState, Shortcut};
+
+// ----------------------------------------------------------------------------
+
+/// The windows of an app that manages its own windows, listed like the system Window menu.
+///
+/// Window ids are chosen by the app, e.g. `u64::from(window_id)` for a winit window.
+#[derive(Debug, Clone, Default)]
+pub struct WindowListMenu {
+    windows: Vec<WindowEntry>,
+    key_window: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowEntry {
+    pub id: u64,
+    pub title: String,
+    /// Has unsaved changes.
+    pub dirty: bool,
+    pub minimized: bool,
+}
+
+/// The mark shown in front of a window's title.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WindowMark {
+    None,
+    /// The key window.
+    Check,
+    /// A minimized window.
+    Diamond,
+    /// A window with unsaved changes.
+    Dot,
+}
+
+/// A window as listed in the menu, see `WindowListMenu::rows()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WindowRow {
+    pub id: u64,
+    pub title: String,
+    pub mark: WindowMark,
+    /// The digit of the window's ⌘1…⌘9 shortcut.
+    pub shortcut_digit: Option<u8>,
+}
+
+impl WindowListMenu {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a window at the end of the list. Does nothing if `id` is already listed.
+    pub fn add(&mut self, id: u64, title: &str) {
+        if self.window(id).is_none() {
+            self.windows.push(WindowEntry {
+                id,
+                title: title.to_string(),
+                dirty: false,
+                minimized: false,
+            });
+        }
+    }
+
+    pub fn remove(&mut self, id: u64) {
+        self.windows.retain(|window| window.id != id);
+        if self.key_window == Some(id) {
+            self.key_window = None;
+        }
+    }
+
+    pub fn set_title(&mut self, id: u64, title: &str) {
+        if let Some(window) = self.window_mut(id) {
+            window.title = title.to_string();
+        }
+    }
+
+    pub fn set_dirty(&mut self, id: u64, dirty: bool) {
+        if let Some(window) = self.window_mut(id) {
+            window.dirty = dirty;
+        }
+    }
+
+    pub fn set_minimized(&mut self, id: u64, minimized: bool) {
+        if let Some(window) = self.window_mut(id) {
+            window.minimized = minimized;
+        }
+    }
+
+    /// Sets the key window, `None` if none of the listed windows is key.
+    pub fn set_key_window(&mut self, id: Option<u64>) {
+        self.key_window = id.filter(|id| self.window(*id).is_some());
+    }
+
+    pub fn key_window(&self) -> Option<u64> {
+        self.key_window
+    }
+
+    pub fn windows(&self) -> &[WindowEntry] {
+        &self.windows
+    }
+
+    pub fn window(&self, id: u64) -> Option<&WindowEntry> {
+        self.windows.iter().find(|window| window.id == id)
+    }
+
+    fn window_mut(&mut self, id: u64) -> Option<&mut WindowEntry> {
+        self.windows.iter_mut().find(|window| window.id == id)
+    }
+
+    /// The windows in menu order, with their marks and shortcuts.
+    ///
+    /// Minimized windows get a diamond, otherwise the key window gets a checkmark and windows
+    /// with unsaved changes a dot. The first nine windows get ⌘1…⌘9.
+    pub fn rows(&self) -> Vec<WindowRow> {
+        self.windows
+            .iter()
+            .enumerate()
+            .map(|(index, window)| {
+                let mark = if window.minimized {
+                    WindowMark::Diamond
+                } else if self.key_window == Some(window.id) {
+                    WindowMark::Check
+                } else if window.dirty {
+                    WindowMark::Dot
+                } else {
+                    WindowMark::None
+                };
+
+                WindowRow {
+                    id: window.id,
+                    title: window.title.clone(),
+                    mark,
+                    shortcut_digit: (index < 9).then(|| index as u8 + 1),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+impl WindowListMenu {
+    /// The items listing the windows, to be added to the app's Window menu.
+    ///
+    /// `on_focus` is called with the id of the chosen window.
+    pub fn menu_items<F>(&self, on_focus: F) -> Vec<MenuItem>
+    where
+        F: Fn(u64) + Send + Sync + 'static,
+    {
+        let on_focus = Arc::new(on_focus);
+
+        self.rows()
+            .into_iter()
+            .map(|row| {
+                let on_focus = on_focus.clone();
+                let id = row.id;
+                let action = Action::callback(move || on_focus(id));
+                let shortcut = row
+                    .shortcut_digit
+                    .map(|digit| Shortcut::new(digit_key(digit)).command(true));
+
+                let item = MenuItem::button(row.title, action, shortcut)
+                    .id(&format!("window-{}", row.id))
+                    .tag(row.id as isize);
+
+                match row.mark {
+                    WindowMark::None => item,
+                    WindowMark::Check => item.state(MenuItemState::On),
+                    WindowMark::Diamond => item
+                        .state(MenuItemState::On)
+                        .image_on(Some(Image::system_symbol("diamond.fill"))),
+                    WindowMark::Dot => item
+                        .state(MenuItemState::On)
+                        .image_on(Some(Image::system_symbol("circle.fill"))),
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn digit_key(digit: u8) -> Key {
+    match digit {
+        1 => Key::Num1,
+        2 => Key::Num2,
+        3 => Key::Num3,
+        4 => Key::Num4,
+        5 => Key::Num5,
+        6 => Key::Num6,
+        7 => Key::Num7,
+        8 => Key::Num8,
+        _ => Key::Num9,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn marks(menu: &WindowListMenu) -> Vec<WindowMark> {
+        menu.rows().iter().map(|row| row.mark).collect()
+    }
+
+    #[test]
+    fn minimized_beats_key_beats_dirty() {
+        let mut menu = WindowListMenu::new();
+        for id in 1..=4 {
+            menu.add(id, &format!("Window {}", id));
+        }
+        menu.set_dirty(1, true);
+        menu.set_minimized(1, true);
+        menu.set_dirty(2, true);
+        menu.set_key_window(Some(2));
+        menu.set_dirty(3, true);
+
+        assert_eq!(
+            marks(&menu),
+            [
+                WindowMark::Diamond,
+                WindowMark::Check,
+                WindowMark::Dot,
+                WindowMark::None
+            ]
+        );
+
+        menu.set_minimized(2, true);
+        assert_eq!(marks(&menu)[1], WindowMark::Diamond);
+    }
+
+    #[test]
+    fn first_nine_windows_get_shortcuts() {
+        let mut menu = WindowListMenu::new();
+        for id in 0..10 {
+            menu.add(id, "Window");
+        }
+
+        let digits: Vec<_> = menu.rows().iter().map(|row| row.shortcut_digit).collect();
+        let expected: Vec<_> = (1..=9).map(Some).chain([None]).collect();
+        assert_eq!(digits, expected);
+    }
+
+    #[test]
+    fn shortcuts_follow_the_list_after_remove() {
+        let mut menu = WindowListMenu::new();
+        menu.add(10, "a");
+        menu.add(20, "b");
+        menu.remove(10);
+
+        let row = &menu.rows()[0];
+        assert_eq!((row.id, row.shortcut_digit), (20, Some(1)));
+    }
+
+    #[test]
+    fn removing_the_key_window_resets_it() {
+        let mut menu = WindowListMenu::new();
+        menu.add(1, "a");
+        menu.add(2, "b");
+        menu.set_key_window(Some(1));
+
+        menu.remove(2);
+        assert_eq!(menu.key_window(), Some(1));
+
+        menu.remove(1);
+        assert_eq!(menu.key_window(), None);
+    }
+
+    #[test]
+    fn unknown_key_window_is_ignored() {
+        let mut menu = WindowListMenu::new();
+        menu.add(1, "a");
+        menu.set_key_window(Some(2));
+
+        assert_eq!(menu.key_window(), None);
+        assert_eq!(marks(&menu), [WindowMark::None]);
+    }
+
+    #[test]
+    fn add_ignores_listed_ids() {
+        let mut menu = WindowListMenu::new();
+        menu.add(1, "a");
+        menu.add(1, "b");
+
+        assert_eq!(menu.windows().len(), 1);
+        assert_eq!(menu.window(1).unwrap().title, "a");
+    }
+}